@@ -0,0 +1,147 @@
+use super::{gui::PANEL_HEIGHT, Map, Player, Position, Renderable, TileType};
+use rltk::{Point, Rltk, RGB};
+use specs::prelude::*;
+
+/// The slice of the map that fits on screen, in map coordinates.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Viewport {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+impl Viewport {
+    pub fn centered_on(center: Point, width: i32, height: i32) -> Viewport {
+        let min_x = center.x - width / 2;
+        let min_y = center.y - height / 2;
+        Viewport {
+            min_x,
+            max_x: min_x + width,
+            min_y,
+            max_y: min_y + height,
+        }
+    }
+
+    /// Translates a map position into console coordinates, if it is on screen.
+    pub fn to_screen(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        if x >= self.min_x && x < self.max_x && y >= self.min_y && y < self.max_y {
+            Some((x - self.min_x, y - self.min_y))
+        } else {
+            None
+        }
+    }
+
+    pub fn to_map(&self, screen_x: i32, screen_y: i32) -> (i32, i32) {
+        (screen_x + self.min_x, screen_y + self.min_y)
+    }
+}
+
+pub fn player_position(ecs: &World) -> Point {
+    let positions = ecs.read_storage::<Position>();
+    let players = ecs.read_storage::<Player>();
+    (&positions, &players)
+        .join()
+        .next()
+        .map(|(pos, _player)| Point::new(pos.x, pos.y))
+        .unwrap_or_else(|| Point::new(0, 0))
+}
+
+/// The map area of the screen: everything above the HUD panel.
+pub fn get_viewport(ecs: &World, ctx: &Rltk) -> Viewport {
+    let (width, height) = ctx.get_char_size();
+    Viewport::centered_on(
+        player_position(ecs),
+        width as i32,
+        height as i32 - PANEL_HEIGHT,
+    )
+}
+
+pub fn render_camera(ecs: &World, ctx: &mut Rltk) {
+    let map = ecs.fetch::<Map>();
+    let viewport = get_viewport(ecs, ctx);
+
+    for (screen_y, y) in (viewport.min_y..viewport.max_y).enumerate() {
+        for (screen_x, x) in (viewport.min_x..viewport.max_x).enumerate() {
+            if map.in_bounds(x, y) {
+                let idx = map.xy_idx(x, y);
+                if map.revealed_tiles[idx] {
+                    let (glyph, mut fg) = get_tile_render(&map.tiles[idx]);
+                    if !map.visible_tiles[idx] {
+                        fg = fg.to_greyscale()
+                    }
+                    ctx.set(screen_x, screen_y, fg, RGB::from_f32(0., 0., 0.), glyph);
+                }
+            } else {
+                ctx.set(
+                    screen_x,
+                    screen_y,
+                    RGB::named(rltk::GRAY),
+                    RGB::named(rltk::BLACK),
+                    rltk::to_cp437('·'),
+                );
+            }
+        }
+    }
+
+    let positions = ecs.read_storage::<Position>();
+    let renderables = ecs.read_storage::<Renderable>();
+
+    let mut data = (&positions, &renderables).join().collect::<Vec<_>>();
+    data.sort_by_key(|(_pos, render)| std::cmp::Reverse(render.render_order));
+    for (pos, render) in data {
+        if !map.visible_tiles[map.xy_idx(pos.x, pos.y)] {
+            continue;
+        }
+        if let Some((screen_x, screen_y)) = viewport.to_screen(pos.x, pos.y) {
+            ctx.set(screen_x, screen_y, render.fg, render.bg, render.glyph);
+        }
+    }
+}
+
+/// Draws `tiles`, laid out like the current map, with everything revealed and nothing
+/// standing on it. The view is centred on the middle of the map.
+pub fn render_snapshot(ecs: &World, tiles: &[TileType], ctx: &mut Rltk) {
+    let map = ecs.fetch::<Map>();
+    let (width, height) = ctx.get_char_size();
+    let viewport = Viewport::centered_on(
+        Point::new(map.width / 2, map.height / 2),
+        width as i32,
+        height as i32 - PANEL_HEIGHT,
+    );
+
+    for (screen_y, y) in (viewport.min_y..viewport.max_y).enumerate() {
+        for (screen_x, x) in (viewport.min_x..viewport.max_x).enumerate() {
+            if map.in_bounds(x, y) {
+                let (glyph, fg) = get_tile_render(&tiles[map.xy_idx(x, y)]);
+                ctx.set(screen_x, screen_y, fg, RGB::from_f32(0., 0., 0.), glyph);
+            }
+        }
+    }
+}
+
+fn get_tile_render(tile: &TileType) -> (u16, RGB) {
+    let glyph;
+    let fg;
+
+    match tile {
+        TileType::Floor => {
+            glyph = rltk::to_cp437('.');
+            fg = RGB::from_f32(0.5, 0.5, 0.5);
+        }
+        TileType::Wall => {
+            glyph = rltk::to_cp437('#');
+            fg = RGB::from_f32(0.0, 1.0, 0.0);
+        }
+        TileType::DownStairs => {
+            glyph = rltk::to_cp437('>');
+            fg = RGB::from_f32(0.0, 1.0, 1.0);
+        }
+        TileType::UpStairs => {
+            glyph = rltk::to_cp437('<');
+            fg = RGB::from_f32(0.0, 1.0, 1.0);
+        }
+    }
+
+    (glyph, fg)
+}
@@ -1,26 +1,258 @@
 use rltk::RGB;
+use serde::{Deserialize, Serialize};
+use specs::error::NoError;
 use specs::prelude::*;
-use specs_derive::Component;
+use specs::saveload::{ConvertSaveload, Marker};
+use specs_derive::{Component, ConvertSaveload};
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
 }
 
-#[derive(Component)]
+/// Stands in for `Position` while an entity waits on a level the player isn't on.
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct OtherLevelPosition {
+    pub x: i32,
+    pub y: i32,
+    pub depth: i32,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Renderable {
     pub glyph: rltk::FontCharType,
     pub fg: RGB,
     pub bg: RGB,
+    /// Lower orders are drawn on top.
+    pub render_order: i32,
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Player {}
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Viewshed {
     pub visible_tiles: Vec<rltk::Point>,
     pub range: i32,
     pub dirty: bool,
 }
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Monster {}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Name {
+    pub name: String,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct BlocksTile {}
+
+/// Nothing can be seen through the tile this stands on.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct BlocksVisibility {}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Door {
+    pub open: bool,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct CombatStats {
+    pub max_hp: i32,
+    pub hp: i32,
+    pub defense: i32,
+    pub power: i32,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Attribute {
+    pub base: i32,
+}
+
+impl Attribute {
+    /// What the attribute adds to the rolls it feeds: nothing at 10 or 11, one more
+    /// for every two points above that (and one less for every two below).
+    pub fn bonus(&self) -> i32 {
+        (self.base - 10).div_euclid(2)
+    }
+}
+
+/// Might hits harder, fitness means more hit points, quickness is harder to hit and
+/// intelligence makes for stronger magic and more mana.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Attributes {
+    pub might: Attribute,
+    pub fitness: Attribute,
+    pub quickness: Attribute,
+    pub intelligence: Attribute,
+}
+
+/// Trained ability, added straight on top of the matching attribute bonus.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Skills {
+    pub melee: i32,
+    pub defense: i32,
+    pub magic: i32,
+}
+
+/// On the player, how far along they are; on a monster, what killing it is worth.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Experience {
+    pub level: i32,
+    pub xp: i32,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Mana {
+    pub max_mana: i32,
+    pub mana: i32,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum HungerState {
+    WellFed,
+    Normal,
+    Hungry,
+    Starving,
+}
+
+/// Counts down the turns until the next pang of hunger.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct HungerClock {
+    pub state: HungerState,
+    pub duration: i32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToMelee {
+    pub target: Entity,
+}
+
+/// Damage taken this turn, each hit flagged with whether the player dealt it, so
+/// the player can be credited with the kill.
+#[derive(Component, Debug)]
+pub struct SufferDamage {
+    pub amount: Vec<(i32, bool)>,
+}
+
+impl SufferDamage {
+    /// Queues damage against `victim`, adding to any already taken this turn.
+    pub fn new_damage(
+        store: &mut WriteStorage<SufferDamage>,
+        victim: Entity,
+        amount: i32,
+        from_player: bool,
+    ) {
+        if let Some(suffering) = store.get_mut(victim) {
+            suffering.amount.push((amount, from_player));
+        } else {
+            let dmg = SufferDamage {
+                amount: vec![(amount, from_player)],
+            };
+            store.insert(victim, dmg).expect("Unable to insert damage");
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Item {}
+
+/// Used up when used.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Consumable {}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct ProvidesHealing {
+    pub heal_amount: i32,
+}
+
+/// Eating it leaves the eater well fed.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct ProvidesFood {}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct InflictsDamage {
+    pub damage: i32,
+}
+
+/// Has to be aimed at a tile no further than `range` away.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Ranged {
+    pub range: i32,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct AreaOfEffect {
+    pub radius: i32,
+}
+
+/// On an item, confuses whatever it hits; on a monster, the turns left before it recovers.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Confusion {
+    pub turns: i32,
+}
+
+#[derive(Component, ConvertSaveload, Debug, Clone)]
+pub struct InBackpack {
+    pub owner: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToPickupItem {
+    pub collected_by: Entity,
+    pub item: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToUseItem {
+    pub item: Entity,
+    pub target: Option<rltk::Point>,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToDropItem {
+    pub item: Entity,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+    Melee,
+    Shield,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Equippable {
+    pub slot: EquipmentSlot,
+}
+
+#[derive(Component, ConvertSaveload, Debug, Clone)]
+pub struct Equipped {
+    pub owner: Entity,
+    pub slot: EquipmentSlot,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct MeleePowerBonus {
+    pub power: i32,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct DefenseBonus {
+    pub defense: i32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToRemoveItem {
+    pub item: Entity,
+}
+
+/// Marks the entities that are written to the save file.
+pub struct SerializeMe;
+
+/// Carries the map through the save file, which only holds components.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct SerializationHelper {
+    pub map: super::map::Map,
+}
@@ -0,0 +1,125 @@
+use super::{
+    gain_xp, gamelog::GameLog, xp_for_kill, Attributes, CombatStats, Experience, Mana, Name,
+    Player, RunStats, Skills, SufferDamage,
+};
+use rltk::{RandomNumberGenerator, RGB};
+use specs::prelude::*;
+
+/// Applies the damage queued this turn. Whatever the player finishes off is worth
+/// experience to them.
+pub struct DamageSystem {}
+
+impl<'a> System<'a> for DamageSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, RunStats>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        Entities<'a>,
+        WriteStorage<'a, CombatStats>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Experience>,
+        WriteStorage<'a, Attributes>,
+        WriteStorage<'a, Skills>,
+        WriteStorage<'a, Mana>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut run_stats,
+            mut log,
+            mut rng,
+            entities,
+            mut stats,
+            mut damage,
+            mut experience,
+            mut attributes,
+            mut skills,
+            mut mana,
+        ) = data;
+
+        let mut xp_gained = 0;
+        for (entity, stats, damage) in (&entities, &mut stats, &damage).join() {
+            let was_alive = stats.hp > 0;
+            let amount = damage.amount.iter().map(|(amount, _)| amount).sum::<i32>();
+            stats.hp -= amount;
+            if entity == *player_entity {
+                run_stats.damage_taken += amount;
+            } else if was_alive
+                && stats.hp < 1
+                && damage.amount.iter().any(|(_, from_player)| *from_player)
+            {
+                if let Some(victim) = experience.get(entity) {
+                    xp_gained += xp_for_kill(victim.level);
+                }
+            }
+        }
+        damage.clear();
+
+        if xp_gained == 0 {
+            return;
+        }
+        let player = *player_entity;
+        if let (Some(experience), Some(attributes), Some(skills), Some(stats)) = (
+            experience.get_mut(player),
+            attributes.get_mut(player),
+            skills.get_mut(player),
+            stats.get_mut(player),
+        ) {
+            let levels = gain_xp(
+                &mut rng,
+                xp_gained,
+                experience,
+                attributes,
+                skills,
+                stats,
+                mana.get_mut(player),
+            );
+            if levels > 0 {
+                log.push(
+                    format!("Congratulations, you are now level {}!", experience.level),
+                    RGB::named(rltk::GOLD),
+                );
+            }
+        }
+    }
+}
+
+/// Deletes everything that ran out of hit points. The player is never deleted;
+/// instead this returns `true` so the caller can end the game.
+pub fn delete_the_dead(ecs: &mut World) -> bool {
+    let mut dead: Vec<Entity> = Vec::new();
+    let mut player_died = false;
+    {
+        let combat_stats = ecs.read_storage::<CombatStats>();
+        let players = ecs.read_storage::<Player>();
+        let names = ecs.read_storage::<Name>();
+        let entities = ecs.entities();
+        let mut log = ecs.write_resource::<GameLog>();
+        for (entity, stats) in (&entities, &combat_stats).join() {
+            if stats.hp < 1 {
+                if players.get(entity).is_some() {
+                    player_died = true;
+                    log.push("You are dead.", RGB::named(rltk::RED));
+                } else {
+                    if let Some(victim_name) = names.get(entity) {
+                        log.push(
+                            format!("{} is dead.", &victim_name.name),
+                            RGB::named(rltk::WHITE),
+                        );
+                    }
+                    dead.push(entity);
+                }
+            }
+        }
+    }
+
+    ecs.write_resource::<RunStats>().kills += dead.len() as i32;
+    for victim in dead {
+        ecs.delete_entity(victim).expect("Unable to delete");
+    }
+
+    player_died
+}
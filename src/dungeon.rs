@@ -0,0 +1,75 @@
+use super::{Map, OtherLevelPosition, Player, Position, Viewshed};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Every level the player has left, as they left it, by depth.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct MasterDungeonMap {
+    maps: HashMap<i32, Map>,
+}
+
+impl MasterDungeonMap {
+    pub fn store_map(&mut self, map: &Map) {
+        self.maps.insert(map.depth, map.clone());
+    }
+
+    /// The level at `depth`, ready to be played again, if it has been visited.
+    pub fn get_map(&self, depth: i32) -> Option<Map> {
+        self.maps.get(&depth).map(|map| {
+            let mut map = map.clone();
+            map.visible_tiles = vec![false; map.tiles.len()];
+            map.tile_content = vec![Vec::new(); map.tiles.len()];
+            map
+        })
+    }
+
+    pub fn deepest(&self) -> Option<i32> {
+        self.maps.keys().copied().max()
+    }
+}
+
+/// Takes everything on the current level, bar the player, off the map and files it
+/// under the level's depth.
+pub fn freeze_level_entities(ecs: &mut World) {
+    let depth = ecs.fetch::<Map>().depth;
+    let entities = ecs.entities();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut other_level_positions = ecs.write_storage::<OtherLevelPosition>();
+    let players = ecs.read_storage::<Player>();
+
+    let to_freeze: Vec<(Entity, i32, i32)> = (&entities, &positions, !&players)
+        .join()
+        .map(|(entity, pos, _player)| (entity, pos.x, pos.y))
+        .collect();
+    for (entity, x, y) in to_freeze {
+        other_level_positions
+            .insert(entity, OtherLevelPosition { x, y, depth })
+            .expect("Insert fail");
+        positions.remove(entity);
+    }
+}
+
+/// Puts back everything that was frozen on the current level.
+pub fn thaw_level_entities(ecs: &mut World) {
+    let depth = ecs.fetch::<Map>().depth;
+    let entities = ecs.entities();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut other_level_positions = ecs.write_storage::<OtherLevelPosition>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+
+    let to_thaw: Vec<(Entity, i32, i32)> = (&entities, &other_level_positions)
+        .join()
+        .filter(|(_entity, pos)| pos.depth == depth)
+        .map(|(entity, pos)| (entity, pos.x, pos.y))
+        .collect();
+    for (entity, x, y) in to_thaw {
+        positions
+            .insert(entity, Position { x, y })
+            .expect("Insert fail");
+        other_level_positions.remove(entity);
+        if let Some(viewshed) = viewsheds.get_mut(entity) {
+            viewshed.dirty = true;
+        }
+    }
+}
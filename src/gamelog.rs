@@ -0,0 +1,28 @@
+use rltk::RGB;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LogEntry {
+    pub text: String,
+    pub color: RGB,
+}
+
+/// Everything that has happened this game, oldest first.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct GameLog {
+    pub entries: Vec<LogEntry>,
+}
+
+impl GameLog {
+    pub fn push<S: ToString>(&mut self, text: S, color: RGB) {
+        self.entries.push(LogEntry {
+            text: text.to_string(),
+            color,
+        });
+    }
+
+    /// The newest `count` entries, newest first.
+    pub fn latest(&self, count: usize) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().rev().take(count)
+    }
+}
@@ -0,0 +1,495 @@
+use super::{
+    camera, defense_bonus, dodge_bonus, gamelog::GameLog, hunger_power_bonus, melee_bonus,
+    power_bonus, targetable_tiles, xp_to_next_level, Attributes, CombatStats, DefenseBonus,
+    Equipped, Experience, HungerClock, HungerState, InBackpack, Mana, Map, MasterDungeonMap,
+    MeleePowerBonus, MenuOption, Name, Player, RunStats, Settings, Skills,
+};
+use rltk::{Point, Rltk, VirtualKeyCode, RGB};
+use specs::prelude::*;
+
+/// Rows at the bottom of the console taken up by the HUD, border included.
+pub const PANEL_HEIGHT: i32 = 7;
+
+pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
+    let (width, height) = ctx.get_char_size();
+    let (width, height) = (width as i32, height as i32);
+    let top = height - PANEL_HEIGHT;
+    ctx.draw_box(
+        0,
+        top,
+        width - 1,
+        PANEL_HEIGHT - 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+
+    let map = ecs.fetch::<Map>();
+    let depth = format!("Depth: {}", map.depth);
+    ctx.print_color(
+        2,
+        top,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        &depth,
+    );
+
+    let combat_stats = ecs.read_storage::<CombatStats>();
+    let players = ecs.read_storage::<Player>();
+    let entities = ecs.entities();
+    let equipped = ecs.read_storage::<Equipped>();
+    let power_bonuses = ecs.read_storage::<MeleePowerBonus>();
+    let defense_bonuses = ecs.read_storage::<DefenseBonus>();
+    let hunger_clocks = ecs.read_storage::<HungerClock>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let skills = ecs.read_storage::<Skills>();
+    let experience = ecs.read_storage::<Experience>();
+    let mana = ecs.read_storage::<Mana>();
+    for (entity, _player, stats) in (&entities, &players, &combat_stats).join() {
+        let hunger = hunger_clocks.get(entity);
+        let (attributes, skills) = (attributes.get(entity), skills.get(entity));
+        let power = stats.power
+            + power_bonus(entity, &equipped, &power_bonuses)
+            + hunger_power_bonus(hunger)
+            + melee_bonus(attributes, skills);
+        let defense = stats.defense
+            + defense_bonus(entity, &equipped, &defense_bonuses)
+            + dodge_bonus(attributes, skills);
+        let effective_stats = format!("Power: {}  Defense: {}", power, defense);
+        let stats_x = width - 2 - effective_stats.len() as i32;
+        ctx.print_color(
+            stats_x,
+            top + PANEL_HEIGHT - 1,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            &effective_stats,
+        );
+
+        // Nothing to say about a normal appetite
+        let status = match hunger.map(|clock| clock.state) {
+            Some(HungerState::WellFed) => Some(("Well Fed", RGB::named(rltk::GREEN))),
+            Some(HungerState::Hungry) => Some(("Hungry", RGB::named(rltk::ORANGE))),
+            Some(HungerState::Starving) => Some(("Starving", RGB::named(rltk::RED))),
+            _ => None,
+        };
+        if let Some(experience) = experience.get(entity) {
+            let mut progress = format!(
+                "Level {}  XP: {} / {}",
+                experience.level,
+                experience.xp,
+                xp_to_next_level(experience.level)
+            );
+            if let Some(mana) = mana.get(entity) {
+                progress += &format!("  Mana: {} / {}", mana.mana, mana.max_mana);
+            }
+            ctx.print_color(
+                2,
+                top + PANEL_HEIGHT - 1,
+                RGB::named(rltk::YELLOW),
+                RGB::named(rltk::BLACK),
+                &progress,
+            );
+        }
+
+        if let Some((status, color)) = status {
+            ctx.print_color(
+                stats_x - 2 - status.len() as i32,
+                top + PANEL_HEIGHT - 1,
+                color,
+                RGB::named(rltk::BLACK),
+                status,
+            );
+        }
+
+        let health = format!(" HP: {} / {} ", stats.hp, stats.max_hp);
+        ctx.print_color(
+            12,
+            top,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            &health,
+        );
+        ctx.draw_bar_horizontal(
+            28,
+            top,
+            width - 30,
+            stats.hp,
+            stats.max_hp,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+        );
+    }
+
+    let log = ecs.fetch::<GameLog>();
+    let lines = (PANEL_HEIGHT - 2) as usize;
+    for (i, entry) in log.latest(lines).enumerate() {
+        ctx.print_color(
+            2,
+            top + 1 + i as i32,
+            entry.color,
+            RGB::named(rltk::BLACK),
+            &entry.text,
+        );
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ItemMenuResult {
+    Cancel,
+    NoResponse,
+    Selected(Entity),
+}
+
+/// Lets the player pick an item from their backpack.
+pub fn show_inventory(ecs: &World, ctx: &mut Rltk, title: &str) -> ItemMenuResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let names = ecs.read_storage::<Name>();
+    let backpack = ecs.read_storage::<InBackpack>();
+    let entities = ecs.entities();
+
+    let inventory: Vec<(Entity, &Name)> = (&entities, &backpack, &names)
+        .join()
+        .filter(|(_entity, item, _name)| item.owner == *player_entity)
+        .map(|(entity, _item, name)| (entity, name))
+        .collect();
+    item_menu(ctx, title, &inventory)
+}
+
+/// Lets the player pick one of the items they have equipped.
+pub fn show_equipped(ecs: &World, ctx: &mut Rltk, title: &str) -> ItemMenuResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let names = ecs.read_storage::<Name>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let entities = ecs.entities();
+
+    let inventory: Vec<(Entity, &Name)> = (&entities, &equipped, &names)
+        .join()
+        .filter(|(_entity, item, _name)| item.owner == *player_entity)
+        .map(|(entity, _item, name)| (entity, name))
+        .collect();
+    item_menu(ctx, title, &inventory)
+}
+
+/// Lists `inventory`, one letter per item, and returns the one picked.
+fn item_menu(ctx: &mut Rltk, title: &str, inventory: &[(Entity, &Name)]) -> ItemMenuResult {
+    let count = inventory.len() as i32;
+
+    let y = 25 - (count / 2);
+    ctx.draw_box(
+        15,
+        y - 2,
+        31,
+        count + 3,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        18,
+        y - 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        title,
+    );
+    ctx.print_color(
+        18,
+        y + count + 1,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "ESCAPE to cancel",
+    );
+
+    for (j, (_entity, name)) in inventory.iter().enumerate() {
+        let y = y + j as i32;
+        ctx.set(
+            17,
+            y,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            rltk::to_cp437('('),
+        );
+        ctx.set(
+            18,
+            y,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            97 + j as rltk::FontCharType,
+        );
+        ctx.set(
+            19,
+            y,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            rltk::to_cp437(')'),
+        );
+
+        ctx.print(21, y, &name.name);
+    }
+
+    match ctx.key {
+        None => ItemMenuResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => ItemMenuResult::Cancel,
+        Some(key) => {
+            let selection = rltk::letter_to_option(key);
+            if selection > -1 && selection < count {
+                ItemMenuResult::Selected(inventory[selection as usize].0)
+            } else {
+                ItemMenuResult::NoResponse
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum TargetResult {
+    Cancel,
+    NoResponse,
+    Selected(Point),
+}
+
+/// Highlights the tiles in range and lets the player click one of them.
+pub fn ranged_target(ecs: &World, ctx: &mut Rltk, range: i32) -> TargetResult {
+    let viewport = camera::get_viewport(ecs, ctx);
+    ctx.print_color(
+        5,
+        0,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "Select Target:",
+    );
+
+    let available_cells = targetable_tiles(ecs, range);
+    for tile in available_cells.iter() {
+        if let Some((screen_x, screen_y)) = viewport.to_screen(tile.x, tile.y) {
+            ctx.set_bg(screen_x, screen_y, RGB::named(rltk::BLUE));
+        }
+    }
+
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let (map_x, map_y) = viewport.to_map(mouse_x, mouse_y);
+    let mouse_map_pos = Point::new(map_x, map_y);
+    let valid_target = available_cells.contains(&mouse_map_pos);
+    let highlight = if valid_target {
+        RGB::named(rltk::CYAN)
+    } else {
+        RGB::named(rltk::RED)
+    };
+    ctx.set_bg(mouse_x, mouse_y, highlight);
+
+    if ctx.key == Some(VirtualKeyCode::Escape) {
+        TargetResult::Cancel
+    } else if ctx.left_click {
+        if valid_target {
+            TargetResult::Selected(mouse_map_pos)
+        } else {
+            TargetResult::Cancel
+        }
+    } else {
+        TargetResult::NoResponse
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum MenuResult {
+    Cancel,
+    NoSelection { selected: MenuOption },
+    Selected { selected: MenuOption },
+}
+
+/// The title screen, with `error` shown below the entries if the last choice failed.
+pub fn main_menu(
+    ctx: &mut Rltk,
+    selection: MenuOption,
+    options: &[MenuOption],
+    error: Option<&str>,
+) -> MenuResult {
+    ctx.cls();
+    ctx.print_color_centered(
+        15,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "Rust Roguelike Tutorial",
+    );
+    ctx.print_color_centered(
+        16,
+        RGB::named(rltk::CYAN),
+        RGB::named(rltk::BLACK),
+        "Use the arrow keys or the mouse; Enter or click to choose",
+    );
+
+    if let Some(error) = error {
+        ctx.print_color_centered(35, RGB::named(rltk::RED), RGB::named(rltk::BLACK), error);
+    }
+
+    let entries: Vec<(MenuOption, &str)> = options
+        .iter()
+        .map(|option| {
+            let label = match option {
+                MenuOption::NewGame => "Begin New Game",
+                MenuOption::Continue => "Continue",
+                MenuOption::Options => "Options",
+                _ => "Quit",
+            };
+            (*option, label)
+        })
+        .collect();
+    menu(ctx, 24, &entries, selection)
+}
+
+/// The escape menu, drawn over the paused game.
+pub fn pause_menu(ctx: &mut Rltk, selection: MenuOption, options: &[MenuOption]) -> MenuResult {
+    let entries: Vec<(MenuOption, &str)> = options
+        .iter()
+        .map(|option| {
+            let label = match option {
+                MenuOption::Resume => "Resume",
+                MenuOption::Options => "Options",
+                _ => "Save and Quit",
+            };
+            (*option, label)
+        })
+        .collect();
+    boxed_menu(ctx, "Paused", &entries, selection)
+}
+
+pub fn options_menu(
+    ctx: &mut Rltk,
+    selection: MenuOption,
+    options: &[MenuOption],
+    settings: Settings,
+) -> MenuResult {
+    let permadeath = if settings.permadeath {
+        "Permadeath: On"
+    } else {
+        "Permadeath: Off"
+    };
+    let entries: Vec<(MenuOption, &str)> = options
+        .iter()
+        .map(|option| {
+            let label = match option {
+                MenuOption::TogglePermadeath => permadeath,
+                _ => "Back",
+            };
+            (*option, label)
+        })
+        .collect();
+    boxed_menu(ctx, "Options", &entries, selection)
+}
+
+/// Sums up the run that just ended.
+pub fn game_over(ecs: &World, ctx: &mut Rltk, options: &[MenuOption]) -> MenuResult {
+    ctx.cls();
+    let stats = ecs.fetch::<RunStats>();
+    let map = ecs.fetch::<Map>();
+    let deepest = ecs
+        .fetch::<MasterDungeonMap>()
+        .deepest()
+        .map_or(map.depth, |depth| depth.max(map.depth));
+    ctx.print_color_centered(
+        15,
+        RGB::named(rltk::RED),
+        RGB::named(rltk::BLACK),
+        "Your journey has ended!",
+    );
+    let summary = [
+        format!("You reached depth {}.", deepest),
+        format!("You lasted {} turns.", stats.turns),
+        format!("You slew {} monsters.", stats.kills),
+        format!("You took {} points of damage.", stats.damage_taken),
+    ];
+    for (i, line) in summary.iter().enumerate() {
+        ctx.print_color_centered(
+            17 + i as i32,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            line,
+        );
+    }
+
+    let entries: Vec<(MenuOption, &str)> = options
+        .iter()
+        .map(|option| (*option, "Return to the Main Menu"))
+        .collect();
+    menu(ctx, 24, &entries, options[0])
+}
+
+/// A menu in a box in the middle of the screen, over whatever is already drawn.
+fn boxed_menu(
+    ctx: &mut Rltk,
+    title: &str,
+    entries: &[(MenuOption, &str)],
+    selection: MenuOption,
+) -> MenuResult {
+    let (width, height) = ctx.get_char_size();
+    let (width, height) = (width as i32, height as i32);
+    let count = entries.len() as i32;
+    let y = height / 2 - count / 2;
+    ctx.draw_box(
+        width / 2 - 15,
+        y - 2,
+        30,
+        count + 3,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color_centered(
+        y - 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        title,
+    );
+    menu(ctx, y, entries, selection)
+}
+
+/// Lists `entries` centred from row `y`. The arrow keys move the highlight, Enter or
+/// a click picks an entry and Escape backs out.
+fn menu(
+    ctx: &mut Rltk,
+    y: i32,
+    entries: &[(MenuOption, &str)],
+    selection: MenuOption,
+) -> MenuResult {
+    let (width, _height) = ctx.get_char_size();
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let mut current = entries
+        .iter()
+        .position(|(option, _label)| *option == selection)
+        .unwrap_or(0);
+    let mut clicked = None;
+
+    for (i, (option, label)) in entries.iter().enumerate() {
+        let row = y + i as i32;
+        let fg = if i == current {
+            RGB::named(rltk::MAGENTA)
+        } else {
+            RGB::named(rltk::WHITE)
+        };
+        ctx.print_color_centered(row, fg, RGB::named(rltk::BLACK), label);
+
+        let left = width as i32 / 2 - label.len() as i32 / 2;
+        if ctx.left_click
+            && mouse_y == row
+            && mouse_x >= left
+            && mouse_x < left + label.len() as i32
+        {
+            clicked = Some(*option);
+        }
+    }
+    if let Some(selected) = clicked {
+        return MenuResult::Selected { selected };
+    }
+
+    let count = entries.len();
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => return MenuResult::Cancel,
+        Some(VirtualKeyCode::Up) => current = (current + count - 1) % count,
+        Some(VirtualKeyCode::Down) => current = (current + 1) % count,
+        Some(VirtualKeyCode::Return) => {
+            return MenuResult::Selected {
+                selected: entries[current].0,
+            }
+        }
+        _ => {}
+    }
+    MenuResult::NoSelection {
+        selected: entries[current].0,
+    }
+}
@@ -0,0 +1,78 @@
+use super::{gamelog::GameLog, HungerClock, HungerState, RunState, SufferDamage};
+use rltk::RGB;
+use specs::prelude::*;
+
+/// Turns spent in each state before sliding into the next.
+pub const WELL_FED_TURNS: i32 = 20;
+pub const NORMAL_TURNS: i32 = 200;
+pub const HUNGRY_TURNS: i32 = 200;
+
+/// Runs hunger clocks down by one each player turn. Starving costs a hit point a turn.
+pub struct HungerSystem {}
+
+impl<'a> System<'a> for HungerSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, HungerClock>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        WriteStorage<'a, SufferDamage>,
+        WriteExpect<'a, GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut hunger_clocks, player_entity, runstate, mut inflict_damage, mut log) =
+            data;
+        if *runstate != RunState::PlayerTurn {
+            return;
+        }
+
+        for (entity, clock) in (&entities, &mut hunger_clocks).join() {
+            clock.duration -= 1;
+            if clock.duration > 0 {
+                continue;
+            }
+            let is_player = entity == *player_entity;
+            match clock.state {
+                HungerState::WellFed => {
+                    clock.state = HungerState::Normal;
+                    clock.duration = NORMAL_TURNS;
+                    if is_player {
+                        log.push("You are no longer well fed.", RGB::named(rltk::ORANGE));
+                    }
+                }
+                HungerState::Normal => {
+                    clock.state = HungerState::Hungry;
+                    clock.duration = HUNGRY_TURNS;
+                    if is_player {
+                        log.push("You are hungry.", RGB::named(rltk::ORANGE));
+                    }
+                }
+                HungerState::Hungry => {
+                    clock.state = HungerState::Starving;
+                    clock.duration = 0;
+                    if is_player {
+                        log.push("You are starving!", RGB::named(rltk::RED));
+                    }
+                }
+                HungerState::Starving => {
+                    if is_player {
+                        log.push(
+                            "Your hunger pangs are getting painful! You suffer 1 hp damage.",
+                            RGB::named(rltk::RED),
+                        );
+                    }
+                    SufferDamage::new_damage(&mut inflict_damage, entity, 1, false);
+                }
+            }
+        }
+    }
+}
+
+/// What going hungry does to melee power: a hungry fighter hits a little softer.
+pub fn hunger_power_bonus(clock: Option<&HungerClock>) -> i32 {
+    match clock.map(|clock| clock.state) {
+        Some(HungerState::Hungry) | Some(HungerState::Starving) => -1,
+        _ => 0,
+    }
+}
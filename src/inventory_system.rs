@@ -0,0 +1,343 @@
+use super::{
+    gamelog::GameLog, magic_bonus, AreaOfEffect, Attributes, CombatStats, Confusion, Consumable,
+    Equippable, Equipped, HungerClock, HungerState, InBackpack, InflictsDamage, Map, Name,
+    Position, ProvidesFood, ProvidesHealing, Skills, SufferDamage, WantsToDropItem,
+    WantsToPickupItem, WantsToRemoveItem, WantsToUseItem, WELL_FED_TURNS,
+};
+use rltk::RGB;
+use specs::prelude::*;
+
+pub struct ItemCollectionSystem {}
+
+impl<'a> System<'a> for ItemCollectionSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, WantsToPickupItem>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, InBackpack>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player_entity, mut gamelog, mut wants_pickup, mut positions, names, mut backpack) =
+            data;
+
+        for pickup in wants_pickup.join() {
+            positions.remove(pickup.item);
+            backpack
+                .insert(
+                    pickup.item,
+                    InBackpack {
+                        owner: pickup.collected_by,
+                    },
+                )
+                .expect("Unable to insert backpack entry");
+
+            if pickup.collected_by == *player_entity {
+                gamelog.push(
+                    format!("You pick up the {}.", name_of(&names, pickup.item)),
+                    RGB::named(rltk::WHITE),
+                );
+            }
+        }
+
+        wants_pickup.clear();
+    }
+}
+
+pub struct ItemUseSystem {}
+
+impl<'a> System<'a> for ItemUseSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, Map>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToUseItem>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Consumable>,
+        ReadStorage<'a, ProvidesHealing>,
+        ReadStorage<'a, ProvidesFood>,
+        WriteStorage<'a, HungerClock>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, AreaOfEffect>,
+        WriteStorage<'a, Confusion>,
+        WriteStorage<'a, CombatStats>,
+        WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, Equippable>,
+        WriteStorage<'a, Equipped>,
+        WriteStorage<'a, InBackpack>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut gamelog,
+            map,
+            entities,
+            mut wants_use,
+            names,
+            consumables,
+            healing,
+            provides_food,
+            mut hunger_clocks,
+            attributes,
+            skills,
+            inflict_damage,
+            aoe,
+            mut confused,
+            mut combat_stats,
+            mut suffer_damage,
+            equippable,
+            mut equipped,
+            mut backpack,
+        ) = data;
+
+        for (entity, useitem) in (&entities, &wants_use).join() {
+            let item_name = name_of(&names, useitem.item);
+
+            // Untargeted items affect the user; targeted ones whatever is at (or around) the target
+            let mut targets: Vec<Entity> = Vec::new();
+            match useitem.target {
+                None => targets.push(entity),
+                Some(target) => match aoe.get(useitem.item) {
+                    None => {
+                        let idx = map.xy_idx(target.x, target.y);
+                        targets.extend(map.tile_content[idx].iter());
+                    }
+                    Some(area_effect) => {
+                        let mut blast_tiles =
+                            rltk::field_of_view(target, area_effect.radius, &*map);
+                        blast_tiles.retain(|p| map.in_bounds(p.x, p.y));
+                        for tile in blast_tiles.iter() {
+                            let idx = map.xy_idx(tile.x, tile.y);
+                            targets.extend(map.tile_content[idx].iter());
+                        }
+                    }
+                },
+            }
+
+            // Equipping swaps out whatever the user already has in that slot; only the
+            // user can be dressed, so aimed items are never equipped
+            if let (Some(can_equip), None) = (equippable.get(useitem.item), useitem.target) {
+                let target_slot = can_equip.slot;
+                let target = entity;
+
+                let to_unequip: Vec<Entity> = (&entities, &equipped)
+                    .join()
+                    .filter(|(_item, already)| {
+                        already.owner == target && already.slot == target_slot
+                    })
+                    .map(|(item, _already)| item)
+                    .collect();
+                for item in to_unequip.iter() {
+                    equipped.remove(*item);
+                    backpack
+                        .insert(*item, InBackpack { owner: target })
+                        .expect("Unable to insert backpack entry");
+                    if target == *player_entity {
+                        gamelog.push(
+                            format!("You unequip {}.", name_of(&names, *item)),
+                            RGB::named(rltk::WHITE),
+                        );
+                    }
+                }
+
+                equipped
+                    .insert(
+                        useitem.item,
+                        Equipped {
+                            owner: target,
+                            slot: target_slot,
+                        },
+                    )
+                    .expect("Unable to insert equipped component");
+                backpack.remove(useitem.item);
+                if target == *player_entity {
+                    gamelog.push(format!("You equip {}.", item_name), RGB::named(rltk::WHITE));
+                }
+            }
+
+            if let Some(healer) = healing.get(useitem.item) {
+                for target in targets.iter() {
+                    if let Some(stats) = combat_stats.get_mut(*target) {
+                        stats.hp = i32::min(stats.max_hp, stats.hp + healer.heal_amount);
+                        if entity == *player_entity {
+                            gamelog.push(
+                                format!(
+                                    "You use the {}, healing {} hp.",
+                                    item_name, healer.heal_amount
+                                ),
+                                RGB::named(rltk::GREEN),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if provides_food.get(useitem.item).is_some() {
+                for target in targets.iter() {
+                    if let Some(clock) = hunger_clocks.get_mut(*target) {
+                        clock.state = HungerState::WellFed;
+                        clock.duration = WELL_FED_TURNS;
+                        if entity == *player_entity {
+                            gamelog.push(
+                                format!("You eat the {}.", item_name),
+                                RGB::named(rltk::GREEN),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let Some(damage) = inflict_damage.get(useitem.item) {
+                let damage = i32::max(
+                    0,
+                    damage.damage + magic_bonus(attributes.get(entity), skills.get(entity)),
+                );
+                for target in targets.iter() {
+                    if combat_stats.get(*target).is_none() {
+                        continue;
+                    }
+                    SufferDamage::new_damage(
+                        &mut suffer_damage,
+                        *target,
+                        damage,
+                        entity == *player_entity,
+                    );
+                    if entity == *player_entity {
+                        let target_name = name_of(&names, *target);
+                        gamelog.push(
+                            format!(
+                                "You use {} on {}, inflicting {} hp.",
+                                item_name, target_name, damage
+                            ),
+                            RGB::named(rltk::ORANGE),
+                        );
+                    }
+                }
+            }
+
+            let confusion_turns = confused.get(useitem.item).map(|confusion| confusion.turns);
+            if let Some(turns) = confusion_turns {
+                for target in targets.iter() {
+                    if combat_stats.get(*target).is_none() {
+                        continue;
+                    }
+                    confused
+                        .insert(*target, Confusion { turns })
+                        .expect("Unable to insert status");
+                    if entity == *player_entity {
+                        let target_name = name_of(&names, *target);
+                        gamelog.push(
+                            format!("You use {} on {}, confusing them.", item_name, target_name),
+                            RGB::named(rltk::MAGENTA),
+                        );
+                    }
+                }
+            }
+
+            if consumables.get(useitem.item).is_some() {
+                entities.delete(useitem.item).expect("Delete failed");
+            }
+        }
+
+        wants_use.clear();
+    }
+}
+
+pub struct ItemDropSystem {}
+
+impl<'a> System<'a> for ItemDropSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToDropItem>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut gamelog,
+            entities,
+            mut wants_drop,
+            names,
+            mut positions,
+            mut backpack,
+        ) = data;
+
+        for (entity, to_drop) in (&entities, &wants_drop).join() {
+            let dropper_pos = match positions.get(entity) {
+                Some(pos) => pos.clone(),
+                None => continue,
+            };
+            positions
+                .insert(to_drop.item, dropper_pos)
+                .expect("Unable to insert position");
+            backpack.remove(to_drop.item);
+
+            if entity == *player_entity {
+                gamelog.push(
+                    format!("You drop the {}.", name_of(&names, to_drop.item)),
+                    RGB::named(rltk::WHITE),
+                );
+            }
+        }
+
+        wants_drop.clear();
+    }
+}
+
+pub struct ItemRemoveSystem {}
+
+impl<'a> System<'a> for ItemRemoveSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToRemoveItem>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Equipped>,
+        WriteStorage<'a, InBackpack>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut gamelog,
+            entities,
+            mut wants_remove,
+            names,
+            mut equipped,
+            mut backpack,
+        ) = data;
+
+        for (entity, to_remove) in (&entities, &wants_remove).join() {
+            equipped.remove(to_remove.item);
+            backpack
+                .insert(to_remove.item, InBackpack { owner: entity })
+                .expect("Unable to insert backpack entry");
+            if entity == *player_entity {
+                gamelog.push(
+                    format!("You unequip {}.", name_of(&names, to_remove.item)),
+                    RGB::named(rltk::WHITE),
+                );
+            }
+        }
+
+        wants_remove.clear();
+    }
+}
+
+/// What to call `entity` in the log, even if it has no name.
+fn name_of<'a>(names: &'a ReadStorage<Name>, entity: Entity) -> &'a str {
+    names.get(entity).map_or("item", |name| name.name.as_str())
+}
@@ -0,0 +1,102 @@
+use super::Command;
+use rltk::VirtualKeyCode;
+use std::collections::HashMap;
+
+/// The bindings used when no keymap file is given; also documents the file format.
+pub const DEFAULT_KEYMAP: &str = include_str!("../keymap.cfg");
+
+/// Maps keys to the command they issue.
+pub struct Keymap {
+    bindings: HashMap<VirtualKeyCode, Command>,
+}
+
+impl Keymap {
+    /// Parses `<key> = <action>` lines; blank lines and `#` comments are ignored.
+    pub fn parse(text: &str) -> Result<Keymap, String> {
+        let mut bindings = HashMap::new();
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, action) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected '<key> = <action>'", line_number + 1))?;
+            let (key, action) = (key.trim(), action.trim());
+            let key = key_from_name(key)
+                .ok_or_else(|| format!("line {}: unknown key '{}'", line_number + 1, key))?;
+            let command = command_from_action(action)
+                .ok_or_else(|| format!("line {}: unknown action '{}'", line_number + 1, action))?;
+            bindings.insert(key, command);
+        }
+
+        Ok(Keymap { bindings })
+    }
+
+    pub fn load(path: &str) -> Result<Keymap, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Keymap::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn command_for(&self, key: VirtualKeyCode) -> Option<Command> {
+        self.bindings.get(&key).copied()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::parse(DEFAULT_KEYMAP).expect("Built-in keymap is invalid")
+    }
+}
+
+fn command_from_action(action: &str) -> Option<Command> {
+    let step = |delta_x, delta_y| Command::Move { delta_x, delta_y };
+    let command = match action {
+        "north" => step(0, -1),
+        "south" => step(0, 1),
+        "west" => step(-1, 0),
+        "east" => step(1, 0),
+        "north_west" => step(-1, -1),
+        "north_east" => step(1, -1),
+        "south_west" => step(-1, 1),
+        "south_east" => step(1, 1),
+        "wait" => Command::Wait,
+        "pickup" => Command::PickUp,
+        "descend" => Command::Descend,
+        "ascend" => Command::Ascend,
+        "inventory" => Command::ShowInventory,
+        "drop" => Command::ShowDropItem,
+        "remove" => Command::ShowRemoveItem,
+        "menu" => Command::ShowPauseMenu,
+        _ => return None,
+    };
+    Some(command)
+}
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        &[$((stringify!($key), VirtualKeyCode::$key)),*]
+    };
+}
+
+/// Keys that can be bound, named as in `VirtualKeyCode`.
+#[rustfmt::skip]
+const KEY_NAMES: &[(&str, VirtualKeyCode)] = key_names!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadDecimal, NumpadEnter,
+    Up, Down, Left, Right, Home, End, PageUp, PageDown, Insert, Delete,
+    Space, Return, Tab, Escape, Back,
+    Period, Comma, Slash, Semicolon, Apostrophe, Minus, Equals, LBracket, RBracket, Backslash,
+    Grave,
+);
+
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _key)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_key_name, key)| *key)
+}
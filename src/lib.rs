@@ -0,0 +1,755 @@
+use rltk::{Point, RandomNumberGenerator, RGB};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::saveload::{SimpleMarker, SimpleMarkerAllocator};
+use std::path::{Path, PathBuf};
+
+pub mod camera;
+mod components;
+pub use components::*;
+pub mod gamelog;
+pub mod gui;
+mod keymap;
+pub use keymap::*;
+mod map;
+pub use map::*;
+mod player;
+pub use player::*;
+mod rect;
+pub use rect::Rect;
+pub mod map_builders;
+mod random_table;
+pub use random_table::RandomTable;
+pub mod raws;
+pub mod spawner;
+mod visibility_system;
+pub use visibility_system::VisibilitySystem;
+mod monster_ai_system;
+pub use monster_ai_system::MonsterAI;
+mod map_indexing_system;
+pub use map_indexing_system::MapIndexingSystem;
+mod melee_combat_system;
+pub use melee_combat_system::{defense_bonus, power_bonus, MeleeCombatSystem};
+mod damage_system;
+pub use damage_system::{delete_the_dead, DamageSystem};
+mod hunger_system;
+pub use hunger_system::{
+    hunger_power_bonus, HungerSystem, HUNGRY_TURNS, NORMAL_TURNS, WELL_FED_TURNS,
+};
+mod progression;
+pub use progression::{
+    dodge_bonus, gain_xp, magic_bonus, melee_bonus, xp_for_kill, xp_to_next_level,
+};
+mod inventory_system;
+pub use inventory_system::{ItemCollectionSystem, ItemDropSystem, ItemRemoveSystem, ItemUseSystem};
+mod dungeon;
+pub mod saveload_system;
+pub use dungeon::{freeze_level_entities, thaw_level_entities, MasterDungeonMap};
+
+pub const WIDTH: i32 = 80;
+pub const HEIGHT: i32 = 50;
+pub const MAP_WIDTH: i32 = 120;
+pub const MAP_HEIGHT: i32 = 80;
+
+/// Something the player wants to do with their turn, independent of how it was input.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Command {
+    Move {
+        delta_x: i32,
+        delta_y: i32,
+    },
+    Wait,
+    PickUp,
+    /// Takes the stairs down, if the player is standing on them.
+    Descend,
+    /// Takes the stairs back up to the previous level.
+    Ascend,
+    ShowInventory,
+    ShowDropItem,
+    ShowRemoveItem,
+    /// Ranged items need a `target`; without one the game asks for it first.
+    UseItem {
+        item: Entity,
+        target: Option<Point>,
+    },
+    DropItem {
+        item: Entity,
+    },
+    /// Takes off an equipped item and puts it back in the backpack.
+    RemoveItem {
+        item: Entity,
+    },
+    /// Backs out of a menu without doing anything.
+    Cancel,
+    /// Pauses the game and brings up the escape menu.
+    ShowPauseMenu,
+    /// Moves the current menu's highlight.
+    Highlight(MenuOption),
+    /// Picks an entry of the current menu.
+    Choose(MenuOption),
+}
+
+/// The entries of the main, pause, options and game-over menus.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum MenuOption {
+    NewGame,
+    Continue,
+    Options,
+    Quit,
+    Resume,
+    SaveAndQuit,
+    TogglePermadeath,
+    Back,
+    MainMenu,
+}
+
+/// Preferences that outlive any one game.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Settings {
+    /// Delete the save when the player dies.
+    pub permadeath: bool,
+    /// Play back how each new level was generated before it is entered.
+    pub show_mapgen: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            permadeath: true,
+            show_mapgen: false,
+        }
+    }
+}
+
+/// Tallies for the game-over screen.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub turns: i32,
+    pub kills: i32,
+    pub damage_taken: i32,
+}
+
+/// Where the game is in its turn cycle; decides which systems run on each tick.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum RunState {
+    AwaitingInput,
+    PreRun,
+    PlayerTurn,
+    MonsterTurn,
+    ShowInventory,
+    ShowDropItem,
+    ShowRemoveItem,
+    ShowTargeting {
+        range: i32,
+        item: Entity,
+    },
+    MainMenu {
+        selection: MenuOption,
+    },
+    PauseMenu {
+        selection: MenuOption,
+    },
+    /// `paused` remembers whether to go back to the pause menu or the main menu.
+    OptionsMenu {
+        selection: MenuOption,
+        paused: bool,
+    },
+    GameOver,
+    /// Replaying the snapshots taken while the level was built, one per tick.
+    MapGeneration {
+        step: usize,
+    },
+}
+
+/// The seed the current game was started from, kept so a run can be reproduced.
+pub struct Seed(pub u64);
+
+/// The simulation: owns the specs `World` and advances it one turn per command.
+pub struct Game {
+    pub ecs: World,
+    save_file: PathBuf,
+    settings: Settings,
+    /// Snapshots of the last level generated, for `RunState::MapGeneration`.
+    mapgen_history: Vec<Vec<TileType>>,
+    /// Why the last menu choice failed, such as a save that would not load.
+    last_error: Option<String>,
+}
+
+impl RunState {
+    /// The same menu with `option` highlighted; other states have no highlight.
+    fn with_selection(self, option: MenuOption) -> RunState {
+        match self {
+            RunState::MainMenu { .. } => RunState::MainMenu { selection: option },
+            RunState::PauseMenu { .. } => RunState::PauseMenu { selection: option },
+            RunState::OptionsMenu { paused, .. } => RunState::OptionsMenu {
+                selection: option,
+                paused,
+            },
+            runstate => runstate,
+        }
+    }
+}
+
+impl Game {
+    /// A world with every component registered but nothing in it.
+    fn empty() -> Game {
+        let mut game = Game {
+            ecs: World::new(),
+            save_file: PathBuf::from(saveload_system::SAVE_FILE),
+            settings: Settings::default(),
+            mapgen_history: Vec::new(),
+            last_error: None,
+        };
+        game.ecs.register::<Position>();
+        game.ecs.register::<OtherLevelPosition>();
+        game.ecs.register::<Renderable>();
+        game.ecs.register::<Player>();
+        game.ecs.register::<Viewshed>();
+        game.ecs.register::<Monster>();
+        game.ecs.register::<Name>();
+        game.ecs.register::<BlocksTile>();
+        game.ecs.register::<BlocksVisibility>();
+        game.ecs.register::<Door>();
+        game.ecs.register::<CombatStats>();
+        game.ecs.register::<HungerClock>();
+        game.ecs.register::<Attributes>();
+        game.ecs.register::<Skills>();
+        game.ecs.register::<Experience>();
+        game.ecs.register::<Mana>();
+        game.ecs.register::<WantsToMelee>();
+        game.ecs.register::<SufferDamage>();
+        game.ecs.register::<Item>();
+        game.ecs.register::<Consumable>();
+        game.ecs.register::<ProvidesHealing>();
+        game.ecs.register::<ProvidesFood>();
+        game.ecs.register::<InflictsDamage>();
+        game.ecs.register::<Ranged>();
+        game.ecs.register::<AreaOfEffect>();
+        game.ecs.register::<Confusion>();
+        game.ecs.register::<Equippable>();
+        game.ecs.register::<Equipped>();
+        game.ecs.register::<MeleePowerBonus>();
+        game.ecs.register::<DefenseBonus>();
+        game.ecs.register::<WantsToRemoveItem>();
+        game.ecs.register::<InBackpack>();
+        game.ecs.register::<WantsToPickupItem>();
+        game.ecs.register::<WantsToUseItem>();
+        game.ecs.register::<WantsToDropItem>();
+        game.ecs.register::<SimpleMarker<SerializeMe>>();
+        game.ecs.register::<SerializationHelper>();
+        game.ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+        game.ecs.insert(RunStats::default());
+        game.ecs.insert(MasterDungeonMap::default());
+        game.ecs.insert(raws::RawMaster::default());
+        game
+    }
+
+    /// Starts a new game whose dungeon is generated entirely from `seed`.
+    pub fn new(seed: u64) -> Game {
+        Game::new_with_raws(seed, raws::RawMaster::default())
+    }
+
+    /// Starts a new game that spawns its monsters, items and props from `raws`.
+    pub fn new_with_raws(seed: u64, raws: raws::RawMaster) -> Game {
+        let mut game = Game::empty();
+        game.ecs.insert(raws);
+        game.ecs.insert(RandomNumberGenerator::seeded(seed));
+        game.ecs.insert(Seed(seed));
+
+        game.generate_level(1);
+
+        let mut log = gamelog::GameLog::default();
+        log.push("Welcome to Rusty Roguelike", RGB::named(rltk::CYAN));
+        game.ecs.insert(log);
+
+        game.ecs.insert(RunState::PreRun);
+        game.tick(None);
+        game
+    }
+
+    /// Builds the level at `depth` with a randomly chosen builder, moves the player
+    /// (created on the first level) to its start and fills the rest.
+    fn generate_level(&mut self, depth: i32) {
+        let mut builder = {
+            let mut rng = self.ecs.write_resource::<RandomNumberGenerator>();
+            let mut builder = map_builders::random_builder(MAP_WIDTH, MAP_HEIGHT, depth, &mut rng);
+            builder.build(&mut rng);
+            builder
+        };
+        self.mapgen_history = builder.get_snapshot_history();
+        let mut map = builder.get_map();
+        let start = builder.get_starting_position();
+        if depth > 1 {
+            let start_idx = map.xy_idx(start.x, start.y);
+            map.tiles[start_idx] = TileType::UpStairs;
+        }
+        map.populate_blocked();
+        self.ecs.insert(map);
+
+        if self.ecs.try_fetch::<Entity>().is_some() {
+            self.place_player(start.x, start.y);
+        } else {
+            self.ecs.insert(Point::new(start.x, start.y));
+            let player_entity = spawner::player(&mut self.ecs, start.x, start.y);
+            self.ecs.insert(player_entity);
+        }
+        builder.spawn_entities(&mut self.ecs);
+    }
+
+    fn place_player(&mut self, x: i32, y: i32) {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        self.ecs.insert(Point::new(x, y));
+        self.ecs
+            .write_storage::<Position>()
+            .insert(player_entity, Position { x, y })
+            .expect("Unable to move player");
+        if let Some(viewshed) = self.ecs.write_storage::<Viewshed>().get_mut(player_entity) {
+            viewshed.dirty = true;
+        }
+    }
+
+    /// Takes the stairs the player is standing on: down for a positive `offset`, up for a
+    /// negative one. The level left behind is kept, entities and all, and comes back
+    /// exactly as it was; a level not seen before is generated.
+    fn goto_level(&mut self, offset: i32) -> RunState {
+        let (stairs, arrival, direction) = if offset > 0 {
+            (TileType::DownStairs, TileType::UpStairs, "down")
+        } else {
+            (TileType::UpStairs, TileType::DownStairs, "up")
+        };
+        let on_stairs = {
+            let map = self.ecs.fetch::<Map>();
+            let player_pos = self.ecs.fetch::<Point>();
+            map.tiles[map.xy_idx(player_pos.x, player_pos.y)] == stairs
+        };
+        if !on_stairs {
+            let mut log = self.ecs.write_resource::<gamelog::GameLog>();
+            log.push(
+                format!("There is no way {} from here.", direction),
+                RGB::named(rltk::GRAY),
+            );
+            return RunState::AwaitingInput;
+        }
+
+        freeze_level_entities(&mut self.ecs);
+        let depth = {
+            let map = self.ecs.fetch::<Map>();
+            self.ecs
+                .write_resource::<MasterDungeonMap>()
+                .store_map(&map);
+            map.depth + offset
+        };
+
+        let stored_map = self.ecs.fetch::<MasterDungeonMap>().get_map(depth);
+        match stored_map {
+            Some(map) => {
+                // Arrive on the stairs that lead back the way we came
+                let (x, y) = map.find_tile(arrival).expect("Level has no stairs back");
+                self.ecs.insert(map);
+                self.place_player(x, y);
+                thaw_level_entities(&mut self.ecs);
+
+                let mut log = self.ecs.write_resource::<gamelog::GameLog>();
+                log.push(
+                    format!("You climb {} to depth {}.", direction, depth),
+                    RGB::named(rltk::MAGENTA),
+                );
+                RunState::PreRun
+            }
+            None => {
+                self.generate_level(depth);
+
+                let player_entity = *self.ecs.fetch::<Entity>();
+                if let Some(stats) = self
+                    .ecs
+                    .write_storage::<CombatStats>()
+                    .get_mut(player_entity)
+                {
+                    stats.hp = i32::max(stats.hp, stats.max_hp / 2);
+                }
+                let mut log = self.ecs.write_resource::<gamelog::GameLog>();
+                log.push(
+                    "You descend to the next level, and take a moment to heal.",
+                    RGB::named(rltk::MAGENTA),
+                );
+                self.enter_new_level()
+            }
+        }
+    }
+
+    /// Restores a game written by `save`, dice and all, so it plays on exactly as the
+    /// original would have.
+    pub fn load(text: &str) -> Result<Game, String> {
+        let mut game = Game::empty();
+        saveload_system::load_game(&mut game.ecs, text)?;
+        game.ecs.insert(RunState::PreRun);
+        game.tick(None);
+        Ok(game)
+    }
+
+    pub fn save(&mut self) -> Result<String, String> {
+        saveload_system::save_game(&mut self.ecs)
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Game, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let game = Game::load(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(game.with_save_file(path))
+    }
+
+    pub fn save_to_file(&mut self, path: &Path) -> Result<(), String> {
+        let text = self.save()?;
+        std::fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Uses `path` instead of `SAVE_FILE` for saving, loading and permadeath.
+    pub fn with_save_file<P: AsRef<Path>>(mut self, path: P) -> Game {
+        self.save_file = path.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn save_file(&self) -> &Path {
+        &self.save_file
+    }
+
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Why the last menu choice failed, until another choice is made.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn with_settings(mut self, settings: Settings) -> Game {
+        self.settings = settings;
+        self
+    }
+
+    /// The tiles to draw while generation is being played back, fully revealed.
+    pub fn mapgen_snapshot(&self) -> Option<&[TileType]> {
+        match self.run_state() {
+            RunState::MapGeneration { step } => self.mapgen_history.get(step).map(Vec::as_slice),
+            _ => None,
+        }
+    }
+
+    /// Where a freshly generated level starts: straight into play, or into a replay of
+    /// its generation when that is switched on.
+    fn enter_new_level(&self) -> RunState {
+        if self.settings.show_mapgen && !self.mapgen_history.is_empty() {
+            RunState::MapGeneration { step: 0 }
+        } else {
+            RunState::PreRun
+        }
+    }
+
+    /// Puts the main menu up over the current (fresh) game.
+    pub fn open_main_menu(&mut self) {
+        *self.ecs.write_resource::<RunState>() = RunState::MainMenu {
+            selection: MenuOption::NewGame,
+        };
+    }
+
+    /// The entries of the menu being shown, if any, in display order.
+    pub fn menu_options(&self) -> Vec<MenuOption> {
+        match self.run_state() {
+            RunState::MainMenu { .. } => {
+                let mut options = vec![MenuOption::NewGame];
+                if saveload_system::does_save_exist(&self.save_file) {
+                    options.push(MenuOption::Continue);
+                }
+                options.extend([MenuOption::Options, MenuOption::Quit]);
+                options
+            }
+            RunState::PauseMenu { .. } => vec![
+                MenuOption::Resume,
+                MenuOption::Options,
+                MenuOption::SaveAndQuit,
+            ],
+            RunState::OptionsMenu { .. } => vec![MenuOption::TogglePermadeath, MenuOption::Back],
+            RunState::GameOver => vec![MenuOption::MainMenu],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Swaps in a freshly generated dungeon, seeded from the current one, for the main
+    /// menu to sit on.
+    fn restart(&mut self) {
+        let seed = self
+            .ecs
+            .write_resource::<RandomNumberGenerator>()
+            .next_u64();
+        let save_file = std::mem::take(&mut self.save_file);
+        let settings = self.settings;
+        let raws = (*self.ecs.fetch::<raws::RawMaster>()).clone();
+        *self = Game::new_with_raws(seed, raws).with_save_file(save_file);
+        self.settings = settings;
+    }
+
+    pub fn run_state(&self) -> RunState {
+        *self.ecs.fetch::<RunState>()
+    }
+
+    /// Advances the run state machine by one step. `input` is only consumed while
+    /// awaiting input or in a menu; the world does not change until the player commits
+    /// an action.
+    pub fn tick(&mut self, input: Option<Command>) -> RunState {
+        let mut newrunstate = match self.run_state() {
+            RunState::PreRun => {
+                self.run_systems();
+                RunState::AwaitingInput
+            }
+            runstate @ (RunState::AwaitingInput
+            | RunState::ShowInventory
+            | RunState::ShowDropItem
+            | RunState::ShowRemoveItem
+            | RunState::ShowTargeting { .. }) => match input {
+                None => runstate,
+                Some(command) => self.apply(command),
+            },
+            RunState::PlayerTurn => {
+                self.run_systems();
+                self.ecs.write_resource::<RunStats>().turns += 1;
+                RunState::MonsterTurn
+            }
+            RunState::MonsterTurn => {
+                self.run_systems();
+                RunState::AwaitingInput
+            }
+            RunState::MapGeneration { step } => match input {
+                Some(Command::Cancel) => RunState::PreRun,
+                _ if step + 1 < self.mapgen_history.len() => {
+                    RunState::MapGeneration { step: step + 1 }
+                }
+                _ => RunState::PreRun,
+            },
+            runstate @ (RunState::MainMenu { .. }
+            | RunState::PauseMenu { .. }
+            | RunState::OptionsMenu { .. }
+            | RunState::GameOver) => match input {
+                Some(Command::Highlight(option)) => runstate.with_selection(option),
+                Some(Command::Choose(option)) => self.choose(runstate, option),
+                Some(Command::Cancel) => self.choose(runstate, MenuOption::Back),
+                _ => runstate,
+            },
+        };
+
+        if newrunstate != RunState::GameOver && delete_the_dead(&mut self.ecs) {
+            newrunstate = RunState::GameOver;
+            if self.settings.permadeath {
+                if let Err(e) = saveload_system::delete_save(&self.save_file) {
+                    let mut log = self.ecs.write_resource::<gamelog::GameLog>();
+                    log.push(
+                        format!("Unable to delete save: {}", e),
+                        RGB::named(rltk::RED),
+                    );
+                }
+            }
+        }
+
+        *self.ecs.write_resource::<RunState>() = newrunstate;
+        newrunstate
+    }
+
+    /// Plays a whole turn: applies the player's command, then ticks until the
+    /// game is waiting for the next one.
+    pub fn submit(&mut self, command: Command) -> RunState {
+        let mut runstate = self.tick(Some(command));
+        while matches!(
+            runstate,
+            RunState::PreRun | RunState::PlayerTurn | RunState::MonsterTurn
+        ) {
+            runstate = self.tick(None);
+        }
+        runstate
+    }
+
+    fn apply(&mut self, command: Command) -> RunState {
+        match command {
+            Command::Move { delta_x, delta_y } => {
+                try_move_player(delta_x, delta_y, &mut self.ecs);
+                RunState::PlayerTurn
+            }
+            Command::Wait => RunState::PlayerTurn,
+            Command::Descend => self.goto_level(1),
+            Command::Ascend => self.goto_level(-1),
+            Command::PickUp => {
+                if get_item(&mut self.ecs) {
+                    RunState::PlayerTurn
+                } else {
+                    RunState::AwaitingInput
+                }
+            }
+            Command::ShowInventory => RunState::ShowInventory,
+            Command::ShowDropItem => RunState::ShowDropItem,
+            Command::ShowRemoveItem => RunState::ShowRemoveItem,
+            Command::UseItem { item, target } => {
+                if !self.carried_by_player(item) {
+                    return RunState::AwaitingInput;
+                }
+                let range = self.ecs.read_storage::<Ranged>().get(item).map(|r| r.range);
+                if let Some(range) = range {
+                    let in_range =
+                        target.is_some_and(|t| targetable_tiles(&self.ecs, range).contains(&t));
+                    if !in_range {
+                        return RunState::ShowTargeting { range, item };
+                    }
+                }
+
+                // Anything not aimed is used on the player
+                let player_entity = *self.ecs.fetch::<Entity>();
+                let target = target.filter(|_| range.is_some());
+                self.ecs
+                    .write_storage::<WantsToUseItem>()
+                    .insert(player_entity, WantsToUseItem { item, target })
+                    .expect("Unable to insert intent");
+                RunState::PlayerTurn
+            }
+            Command::DropItem { item } => {
+                if !self.carried_by_player(item) {
+                    return RunState::AwaitingInput;
+                }
+                let player_entity = *self.ecs.fetch::<Entity>();
+                self.ecs
+                    .write_storage::<WantsToDropItem>()
+                    .insert(player_entity, WantsToDropItem { item })
+                    .expect("Unable to insert intent");
+                RunState::PlayerTurn
+            }
+            Command::RemoveItem { item } => {
+                let player_entity = *self.ecs.fetch::<Entity>();
+                let equipped_by_player = self
+                    .ecs
+                    .read_storage::<Equipped>()
+                    .get(item)
+                    .is_some_and(|equipped| equipped.owner == player_entity);
+                if !equipped_by_player {
+                    return RunState::AwaitingInput;
+                }
+                self.ecs
+                    .write_storage::<WantsToRemoveItem>()
+                    .insert(player_entity, WantsToRemoveItem { item })
+                    .expect("Unable to insert intent");
+                RunState::PlayerTurn
+            }
+            Command::Cancel => RunState::AwaitingInput,
+            Command::ShowPauseMenu => RunState::PauseMenu {
+                selection: MenuOption::Resume,
+            },
+            Command::Highlight(_) | Command::Choose(_) => RunState::AwaitingInput,
+        }
+    }
+
+    /// Whether `item` is in the player's backpack; commands naming anything else
+    /// (something on the floor, a monster, an item already used up) are refused.
+    fn carried_by_player(&self, item: Entity) -> bool {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        self.ecs
+            .read_storage::<InBackpack>()
+            .get(item)
+            .is_some_and(|pack| pack.owner == player_entity)
+    }
+
+    /// Acts on a menu entry. `Back` doubles as what Escape does in each menu.
+    fn choose(&mut self, runstate: RunState, option: MenuOption) -> RunState {
+        if !self.menu_options().contains(&option) {
+            // Escape leaves the pause menu; anything else not on show does nothing
+            return match runstate {
+                RunState::PauseMenu { .. } if option == MenuOption::Back => RunState::AwaitingInput,
+                _ => runstate,
+            };
+        }
+
+        self.last_error = None;
+        match option {
+            MenuOption::NewGame => self.enter_new_level(),
+            MenuOption::Continue => match Game::load_from_file(&self.save_file) {
+                Ok(game) => {
+                    let settings = self.settings;
+                    let raws = (*self.ecs.fetch::<raws::RawMaster>()).clone();
+                    *self = game;
+                    self.settings = settings;
+                    self.ecs.insert(raws);
+                    RunState::PreRun
+                }
+                Err(e) => {
+                    self.last_error = Some(format!("Unable to load: {}", e));
+                    runstate
+                }
+            },
+            MenuOption::Options => RunState::OptionsMenu {
+                selection: MenuOption::TogglePermadeath,
+                paused: matches!(runstate, RunState::PauseMenu { .. }),
+            },
+            MenuOption::Resume => RunState::AwaitingInput,
+            MenuOption::SaveAndQuit => match self.save_to_file(&self.save_file.clone()) {
+                Ok(()) => {
+                    self.restart();
+                    RunState::MainMenu {
+                        selection: MenuOption::Continue,
+                    }
+                }
+                Err(e) => {
+                    let error = format!("Unable to save: {}", e);
+                    let mut log = self.ecs.write_resource::<gamelog::GameLog>();
+                    log.push(&error, RGB::named(rltk::RED));
+                    self.last_error = Some(error);
+                    RunState::AwaitingInput
+                }
+            },
+            MenuOption::TogglePermadeath => {
+                self.settings.permadeath = !self.settings.permadeath;
+                runstate
+            }
+            MenuOption::Back => match runstate {
+                RunState::OptionsMenu { paused: true, .. } => RunState::PauseMenu {
+                    selection: MenuOption::Options,
+                },
+                _ => RunState::MainMenu {
+                    selection: MenuOption::Options,
+                },
+            },
+            MenuOption::MainMenu => {
+                self.restart();
+                RunState::MainMenu {
+                    selection: MenuOption::NewGame,
+                }
+            }
+            // Leaving is up to the front-end
+            MenuOption::Quit => runstate,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.ecs.fetch::<Seed>().0
+    }
+
+    fn run_systems(&mut self) {
+        // Indexing first, so closed doors are known to block sight
+        let mut mapindex = MapIndexingSystem {};
+        mapindex.run_now(&self.ecs);
+        let mut vis = VisibilitySystem {};
+        vis.run_now(&self.ecs);
+        let mut mob = MonsterAI {};
+        mob.run_now(&self.ecs);
+        let mut melee = MeleeCombatSystem {};
+        melee.run_now(&self.ecs);
+        let mut pickup = ItemCollectionSystem {};
+        pickup.run_now(&self.ecs);
+        let mut items = ItemUseSystem {};
+        items.run_now(&self.ecs);
+        let mut drop_items = ItemDropSystem {};
+        drop_items.run_now(&self.ecs);
+        let mut item_remove = ItemRemoveSystem {};
+        item_remove.run_now(&self.ecs);
+        let mut hunger = HungerSystem {};
+        hunger.run_now(&self.ecs);
+        let mut damage = DamageSystem {};
+        damage.run_now(&self.ecs);
+        // And again last, so the player's next move sees where everything ended up
+        mapindex.run_now(&self.ecs);
+        self.ecs.maintain();
+    }
+}
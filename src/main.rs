@@ -1,83 +1,184 @@
-use rltk::{GameState, Rltk, RGB};
-use specs::prelude::*;
+use gui::{ItemMenuResult, MenuResult, TargetResult};
+use rltk::{GameState, RandomNumberGenerator, Rltk, VirtualKeyCode};
+use rust_roguelike::raws::RawMaster;
+use rust_roguelike::*;
 
-mod components;
-pub use components::*;
-mod map;
-pub use map::*;
-mod player;
-pub use player::*;
-mod rect;
-pub use rect::Rect;
-mod visibility_system;
-use visibility_system::VisibilitySystem;
+/// How long each map generation snapshot stays on screen.
+const MAPGEN_FRAME_TIME: f32 = 100.0;
 
-const WIDTH: i32 = 80;
-const HEIGHT: i32 = 50;
-
-pub struct State {
-    ecs: World,
+struct State {
+    game: Game,
+    keymap: Keymap,
+    mapgen_timer: f32,
 }
 
 impl GameState for State {
     fn tick(&mut self, ctx: &mut Rltk) {
         ctx.cls();
-        player_input(self, ctx);
-        self.run_systems();
+        let runstate = self.game.run_state();
+        if let Some(tiles) = self.game.mapgen_snapshot() {
+            camera::render_snapshot(&self.game.ecs, tiles, ctx);
+            ctx.print(1, HEIGHT - 1, "Generating the level... (Escape to skip)");
+            self.mapgen_timer += ctx.frame_time_ms;
+            if ctx.key == Some(VirtualKeyCode::Escape) {
+                self.game.tick(Some(Command::Cancel));
+            } else if self.mapgen_timer > MAPGEN_FRAME_TIME {
+                self.mapgen_timer = 0.0;
+                self.game.tick(None);
+            }
+            return;
+        }
+        if !matches!(runstate, RunState::MainMenu { .. } | RunState::GameOver) {
+            camera::render_camera(&self.game.ecs, ctx);
+            gui::draw_ui(&self.game.ecs, ctx);
+        }
 
-        draw_map(&self.ecs, ctx);
+        let input = match runstate {
+            RunState::MainMenu { selection } => {
+                let options = self.game.menu_options();
+                let result = gui::main_menu(ctx, selection, &options, self.game.last_error());
+                menu_input(ctx, result, selection)
+            }
+            RunState::PauseMenu { selection } => {
+                let options = self.game.menu_options();
+                let result = gui::pause_menu(ctx, selection, &options);
+                menu_input(ctx, result, selection)
+            }
+            RunState::OptionsMenu { selection, .. } => {
+                let options = self.game.menu_options();
+                let result = gui::options_menu(ctx, selection, &options, self.game.settings());
+                menu_input(ctx, result, selection)
+            }
+            RunState::GameOver => {
+                let options = self.game.menu_options();
+                let result = gui::game_over(&self.game.ecs, ctx, &options);
+                menu_input(ctx, result, options[0])
+            }
+            RunState::ShowInventory => {
+                match gui::show_inventory(&self.game.ecs, ctx, "Inventory") {
+                    ItemMenuResult::Cancel => Some(Command::Cancel),
+                    ItemMenuResult::NoResponse => None,
+                    ItemMenuResult::Selected(item) => Some(Command::UseItem { item, target: None }),
+                }
+            }
+            RunState::ShowDropItem => {
+                match gui::show_inventory(&self.game.ecs, ctx, "Drop which item?") {
+                    ItemMenuResult::Cancel => Some(Command::Cancel),
+                    ItemMenuResult::NoResponse => None,
+                    ItemMenuResult::Selected(item) => Some(Command::DropItem { item }),
+                }
+            }
+            RunState::ShowRemoveItem => {
+                match gui::show_equipped(&self.game.ecs, ctx, "Remove which item?") {
+                    ItemMenuResult::Cancel => Some(Command::Cancel),
+                    ItemMenuResult::NoResponse => None,
+                    ItemMenuResult::Selected(item) => Some(Command::RemoveItem { item }),
+                }
+            }
+            RunState::ShowTargeting { range, item } => {
+                match gui::ranged_target(&self.game.ecs, ctx, range) {
+                    TargetResult::Cancel => Some(Command::Cancel),
+                    TargetResult::NoResponse => None,
+                    TargetResult::Selected(target) => Some(Command::UseItem {
+                        item,
+                        target: Some(target),
+                    }),
+                }
+            }
+            _ => player_input(ctx, &self.keymap),
+        };
+        self.game.tick(input);
+    }
+}
 
-        let positions = self.ecs.read_storage::<Position>();
-        let renderables = self.ecs.read_storage::<Renderable>();
+/// Turns a menu's response into a command; picking "Quit" closes the window.
+fn menu_input(ctx: &mut Rltk, result: MenuResult, selection: MenuOption) -> Option<Command> {
+    match result {
+        MenuResult::Cancel => Some(Command::Cancel),
+        MenuResult::Selected {
+            selected: MenuOption::Quit,
+        } => {
+            ctx.quit();
+            None
+        }
+        MenuResult::Selected { selected } => Some(Command::Choose(selected)),
+        MenuResult::NoSelection { selected } if selected != selection => {
+            Some(Command::Highlight(selected))
+        }
+        MenuResult::NoSelection { .. } => None,
+    }
+}
 
-        for (pos, render) in (&positions, &renderables).join() {
-            ctx.set(pos.x, pos.y, render.fg, render.bg, render.glyph);
+/// Reads `<name> <value>` (or `<name>=<value>`) from the command line.
+fn option_value<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            return match iter.next() {
+                Some(value) => Ok(Some(value)),
+                None => Err(format!("{} needs a value", name)),
+            };
+        } else if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Ok(Some(value));
         }
     }
+    Ok(None)
 }
 
-impl State {
-    fn run_systems(&mut self) {
-        let mut vis = VisibilitySystem {};
-        vis.run_now(&self.ecs);
-        self.ecs.maintain();
+fn parse_seed(args: &[String]) -> Result<Option<u64>, String> {
+    match option_value(args, "--seed")? {
+        None => Ok(None),
+        Some(value) => value
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|_| format!("invalid seed '{}'", value)),
+    }
+}
+
+/// Uses `--keymap <path>` if given, otherwise `keymap.cfg` if there is one, otherwise the defaults.
+fn load_keymap(args: &[String]) -> Result<Keymap, String> {
+    match option_value(args, "--keymap")? {
+        Some(path) => Keymap::load(path),
+        None if std::path::Path::new("keymap.cfg").exists() => Keymap::load("keymap.cfg"),
+        None => Ok(Keymap::default()),
+    }
+}
+
+/// Uses `--raws <path>` if given, otherwise `raws/spawns.json` if there is one,
+/// otherwise the built-in raws.
+fn load_raws(args: &[String]) -> Result<RawMaster, String> {
+    match option_value(args, "--raws")? {
+        Some(path) => RawMaster::load(path),
+        None if std::path::Path::new("raws/spawns.json").exists() => {
+            RawMaster::load("raws/spawns.json")
+        }
+        None => Ok(RawMaster::default()),
     }
 }
 
 fn main() -> rltk::BError {
     use rltk::RltkBuilder;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let seed = parse_seed(&args)?.unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    let keymap = load_keymap(&args)?;
+    let raws = load_raws(&args)?;
+    let settings = Settings {
+        show_mapgen: args.iter().any(|arg| arg == "--show-mapgen"),
+        ..Settings::default()
+    };
+    println!("Seed: {}", seed);
+
     let context = RltkBuilder::simple(WIDTH, HEIGHT)
         .unwrap()
         .with_title("Roguelike Tutorial")
         .build()?;
-    let mut gs = State { ecs: World::new() };
-    gs.ecs.register::<Position>();
-    gs.ecs.register::<Renderable>();
-    gs.ecs.register::<Player>();
-    gs.ecs.register::<Viewshed>();
-
-    let map: Map = Map::new_map_rooms_and_corridors();
-    let (player_x, player_y) = map.rooms[0].center();
-    gs.ecs.insert(map);
-
-    gs.ecs
-        .create_entity()
-        .with(Position {
-            x: player_x,
-            y: player_y,
-        })
-        .with(Renderable {
-            glyph: rltk::to_cp437('@'),
-            fg: RGB::named(rltk::YELLOW),
-            bg: RGB::named(rltk::BLACK),
-        })
-        .with(Player {})
-        .with(Viewshed {
-            visible_tiles: Vec::new(),
-            range: 8,
-            dirty: true,
-        })
-        .build();
+    let mut game = Game::new_with_raws(seed, raws).with_settings(settings);
+    game.open_main_menu();
+    let gs = State {
+        game,
+        keymap,
+        mapgen_timer: 0.0,
+    };
 
     rltk::main_loop(context, gs)
 }
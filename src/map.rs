@@ -1,164 +1,139 @@
 use super::Rect;
-use rltk::{Algorithm2D, BaseMap, Point, RandomNumberGenerator, Rltk, RGB};
+use rltk::{Algorithm2D, BaseMap, DistanceAlg, Point, SmallVec};
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
-use std::cmp::{max, min};
+use std::collections::HashSet;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum TileType {
     Wall,
     Floor,
+    DownStairs,
+    UpStairs,
 }
 
-type Rooms = Vec<Rect>;
-
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize, Clone)]
 pub struct Map {
+    pub width: i32,
+    pub height: i32,
     pub tiles: Vec<TileType>,
-    pub rooms: Rooms,
+    pub rooms: Vec<Rect>,
     pub revealed_tiles: Vec<bool>,
     pub visible_tiles: Vec<bool>,
+    pub blocked: Vec<bool>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub tile_content: Vec<Vec<Entity>>,
+    /// Tiles that can't be seen through although they aren't wall, such as closed
+    /// doors; rebuilt by the indexing system.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub view_blocked: HashSet<usize>,
+    pub depth: i32,
 }
 
 impl Map {
-    pub fn new_map_rooms_and_corridors() -> Map {
-        const MAP_LENGTH: usize = crate::WIDTH as usize * crate::HEIGHT as usize;
-        let mut map = Map {
-            tiles: vec![TileType::Wall; MAP_LENGTH],
+    /// A solid block of wall, `width` by `height` tiles.
+    pub fn new(width: i32, height: i32) -> Map {
+        let map_length = width as usize * height as usize;
+        Map {
+            width,
+            height,
+            tiles: vec![TileType::Wall; map_length],
             rooms: Vec::new(),
-            revealed_tiles: vec![false; MAP_LENGTH],
-            visible_tiles: vec![false; MAP_LENGTH],
-        };
-
-        map.add_rooms_and_corridors();
-        map
+            revealed_tiles: vec![false; map_length],
+            visible_tiles: vec![false; map_length],
+            blocked: vec![false; map_length],
+            tile_content: vec![Vec::new(); map_length],
+            view_blocked: HashSet::new(),
+            depth: 1,
+        }
     }
 
-    fn add_rooms_and_corridors(&mut self) {
-        let mut rng = RandomNumberGenerator::new();
-        let mut rooms: Rooms = Vec::new();
-        const MAX_ROOMS: i32 = 30;
-        const MIN_SIZE: i32 = 6;
-        const MAX_SIZE: i32 = 10;
-
-        for _ in 0..MAX_ROOMS {
-            let w = rng.range(MIN_SIZE, MAX_SIZE);
-            let h = rng.range(MIN_SIZE, MAX_SIZE);
-            let x = rng.roll_dice(1, crate::WIDTH - w - 1) - 1;
-            let y = rng.roll_dice(1, crate::HEIGHT - h - 1) - 1;
-            let new_room = Rect::new(x, y, w, h);
-            let mut ok = true;
-
-            for other_room in rooms.iter() {
-                if new_room.intersect(other_room) {
-                    ok = false;
-                }
-            }
-
-            if ok {
-                self.apply_room_to_map(&new_room);
-                if !rooms.is_empty() {
-                    self.connect_room_to_previous_room(&new_room, &rooms[rooms.len() - 1]);
-                }
-                rooms.push(new_room);
-            }
-        }
+    pub fn xy_idx(&self, x: i32, y: i32) -> usize {
+        (y as usize * self.width as usize) + x as usize
+    }
 
-        self.rooms = rooms;
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
     }
 
-    fn connect_room_to_previous_room(&mut self, new_room: &Rect, prev_room: &Rect) {
-        let mut rng = RandomNumberGenerator::new();
-        let (new_x, new_y) = new_room.center();
-        let (prev_x, prev_y) = prev_room.center();
-        if rng.range(0, 2) == 1 {
-            self.apply_horizontal_tunnel(prev_x, new_x, prev_y);
-            self.apply_vertical_tunnel(prev_y, new_y, new_x);
-        } else {
-            self.apply_horizontal_tunnel(prev_x, new_x, new_y);
-            self.apply_vertical_tunnel(prev_y, new_y, prev_x);
+    /// A diagonal step from `(x, y)` may not squeeze between two walls touching at a corner.
+    pub fn cuts_wall_corner(&self, x: i32, y: i32, delta_x: i32, delta_y: i32) -> bool {
+        if delta_x == 0 || delta_y == 0 {
+            return false;
         }
+        let is_wall = |x: i32, y: i32| {
+            !self.in_bounds(x, y) || self.tiles[self.xy_idx(x, y)] == TileType::Wall
+        };
+        is_wall(x + delta_x, y) && is_wall(x, y + delta_y)
     }
 
-    fn apply_room_to_map(&mut self, room: &Rect) {
-        for y in room.y1 + 1..=room.y2 {
-            for x in room.x1 + 1..=room.x2 {
-                let idx = self.xy_idx(x, y);
-                self.tiles[idx] = TileType::Floor;
-            }
-        }
+    fn is_exit_valid(&self, x: i32, y: i32, delta_x: i32, delta_y: i32) -> bool {
+        let (destination_x, destination_y) = (x + delta_x, y + delta_y);
+        self.in_bounds(destination_x, destination_y)
+            && !self.blocked[self.xy_idx(destination_x, destination_y)]
+            && !self.cuts_wall_corner(x, y, delta_x, delta_y)
     }
 
-    fn apply_horizontal_tunnel(&mut self, x1: i32, x2: i32, y: i32) {
-        for x in min(x1, x2)..=max(x1, x2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < crate::WIDTH as usize * crate::HEIGHT as usize {
-                self.tiles[idx] = TileType::Floor;
-            }
+    /// Resets `blocked` to just the walls; blocking entities are added back by the indexing system.
+    pub fn populate_blocked(&mut self) {
+        for (i, tile) in self.tiles.iter().enumerate() {
+            self.blocked[i] = *tile == TileType::Wall;
         }
     }
 
-    fn apply_vertical_tunnel(&mut self, y1: i32, y2: i32, x: i32) {
-        for y in min(y1, y2)..=max(y1, y2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < crate::WIDTH as usize * crate::HEIGHT as usize {
-                self.tiles[idx] = TileType::Floor;
-            }
-        }
+    /// Where the first tile of type `tile` is, if the map has one.
+    pub fn find_tile(&self, tile: TileType) -> Option<(i32, i32)> {
+        self.tiles
+            .iter()
+            .position(|t| *t == tile)
+            .map(|idx| (idx as i32 % self.width, idx as i32 / self.width))
     }
 
-    pub fn xy_idx(&self, x: i32, y: i32) -> usize {
-        (y as usize * crate::WIDTH as usize) + x as usize
+    pub fn clear_content_index(&mut self) {
+        for content in self.tile_content.iter_mut() {
+            content.clear();
+        }
     }
 }
 
 impl Algorithm2D for Map {
     fn dimensions(&self) -> Point {
-        Point::new(crate::WIDTH, crate::HEIGHT)
+        Point::new(self.width, self.height)
     }
 }
 
 impl BaseMap for Map {
     fn is_opaque(&self, idx: usize) -> bool {
-        self.tiles[idx as usize] == TileType::Wall
+        self.tiles[idx] == TileType::Wall || self.view_blocked.contains(&idx)
     }
-}
-
-pub fn draw_map(ecs: &World, ctx: &mut Rltk) {
-    let map = ecs.fetch::<Map>();
 
-    let mut y = 0;
-    let mut x = 0;
-    for (idx, tile) in map.tiles.iter().enumerate() {
-        if map.revealed_tiles[idx] {
-            let (glyph, mut fg) = get_tile_render(tile);
-            if !map.visible_tiles[idx] {
-                fg = fg.to_greyscale()
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let x = idx as i32 % self.width;
+        let y = idx as i32 / self.width;
+
+        for (delta_x, delta_y, cost) in [
+            (-1, 0, 1.0),
+            (1, 0, 1.0),
+            (0, -1, 1.0),
+            (0, 1, 1.0),
+            (-1, -1, 1.45),
+            (1, -1, 1.45),
+            (-1, 1, 1.45),
+            (1, 1, 1.45),
+        ] {
+            if self.is_exit_valid(x, y, delta_x, delta_y) {
+                exits.push((self.xy_idx(x + delta_x, y + delta_y), cost));
             }
-            ctx.set(x, y, fg, RGB::from_f32(0., 0., 0.), glyph);
         }
 
-        x += 1;
-        if x > 79 {
-            x = 0;
-            y += 1;
-        }
+        exits
     }
-}
-
-fn get_tile_render(tile: &TileType) -> (u16, RGB) {
-    let glyph;
-    let fg;
 
-    match tile {
-        TileType::Floor => {
-            glyph = rltk::to_cp437('.');
-            fg = RGB::from_f32(0.5, 0.5, 0.5);
-        }
-        TileType::Wall => {
-            glyph = rltk::to_cp437('#');
-            fg = RGB::from_f32(0.0, 1.0, 0.0);
-        }
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        let w = self.width as usize;
+        let p1 = Point::new(idx1 % w, idx1 / w);
+        let p2 = Point::new(idx2 % w, idx2 / w);
+        DistanceAlg::Pythagoras.distance2d(p1, p2)
     }
-
-    (glyph, fg)
 }
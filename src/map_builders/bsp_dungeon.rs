@@ -0,0 +1,113 @@
+use super::{apply_room_to_map, BuilderMap, InitialMapBuilder, Map, Rect, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Binary space partitioning: the map is split into ever smaller rectangles and a
+/// room is placed inside some of them. Rooms are listed left to right.
+pub struct BspDungeonBuilder {
+    rects: Vec<Rect>,
+}
+
+impl InitialMapBuilder for BspDungeonBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut rooms: Vec<Rect> = Vec::new();
+        self.rects.clear();
+        self.rects.push(Rect::new(
+            2,
+            2,
+            build_data.map.width - 5,
+            build_data.map.height - 5,
+        ));
+        let first_room = self.rects[0];
+        self.add_subrects(first_room);
+
+        // Keep cutting up random rectangles and trying to fit rooms in the pieces
+        let mut n_rooms = 0;
+        while n_rooms < 240 {
+            let rect = self.get_random_rect(rng);
+            let candidate = self.get_random_sub_rect(rect, rng);
+
+            if self.is_possible(candidate, &build_data.map) {
+                apply_room_to_map(&mut build_data.map, &candidate);
+                build_data.take_snapshot();
+                rooms.push(candidate);
+                self.add_subrects(rect);
+            }
+            n_rooms += 1;
+        }
+
+        // Sorting by left edge keeps the corridors short
+        rooms.sort_by_key(|room| room.x1);
+        build_data.rooms = Some(rooms);
+    }
+}
+
+impl BspDungeonBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<BspDungeonBuilder> {
+        Box::new(BspDungeonBuilder { rects: Vec::new() })
+    }
+
+    /// Splits `rect` into quarters.
+    fn add_subrects(&mut self, rect: Rect) {
+        let width = i32::abs(rect.x1 - rect.x2);
+        let height = i32::abs(rect.y1 - rect.y2);
+        let half_width = i32::max(width / 2, 1);
+        let half_height = i32::max(height / 2, 1);
+
+        self.rects
+            .push(Rect::new(rect.x1, rect.y1, half_width, half_height));
+        self.rects.push(Rect::new(
+            rect.x1,
+            rect.y1 + half_height,
+            half_width,
+            half_height,
+        ));
+        self.rects.push(Rect::new(
+            rect.x1 + half_width,
+            rect.y1,
+            half_width,
+            half_height,
+        ));
+        self.rects.push(Rect::new(
+            rect.x1 + half_width,
+            rect.y1 + half_height,
+            half_width,
+            half_height,
+        ));
+    }
+
+    fn get_random_rect(&self, rng: &mut RandomNumberGenerator) -> Rect {
+        if self.rects.len() == 1 {
+            return self.rects[0];
+        }
+        let idx = (rng.roll_dice(1, self.rects.len() as i32) - 1) as usize;
+        self.rects[idx]
+    }
+
+    /// A room of random size somewhere inside `rect`.
+    fn get_random_sub_rect(&self, rect: Rect, rng: &mut RandomNumberGenerator) -> Rect {
+        let rect_width = i32::abs(rect.x1 - rect.x2);
+        let rect_height = i32::abs(rect.y1 - rect.y2);
+
+        let w = i32::max(3, rng.roll_dice(1, i32::min(rect_width, 10)) - 1) + 1;
+        let h = i32::max(3, rng.roll_dice(1, i32::min(rect_height, 10)) - 1) + 1;
+        let x = rect.x1 + rng.roll_dice(1, 6) - 1;
+        let y = rect.y1 + rng.roll_dice(1, 6) - 1;
+        Rect::new(x, y, w, h)
+    }
+
+    /// Whether `rect`, plus a border of one, lies on the map and on nothing but wall.
+    fn is_possible(&self, rect: Rect, map: &Map) -> bool {
+        for y in rect.y1 - 2..=rect.y2 + 2 {
+            for x in rect.x1 - 2..=rect.x2 + 2 {
+                if x > map.width - 2 || y > map.height - 2 || x < 1 || y < 1 {
+                    return false;
+                }
+                if map.tiles[map.xy_idx(x, y)] != TileType::Wall {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
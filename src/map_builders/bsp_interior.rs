@@ -0,0 +1,78 @@
+use super::{apply_room_to_map, BuilderMap, InitialMapBuilder, Rect};
+use rltk::RandomNumberGenerator;
+
+const MIN_ROOM_SIZE: i32 = 8;
+
+/// Binary space partitioning with no gaps: the whole map is carved into rooms that
+/// share walls, like the inside of a building.
+pub struct BspInteriorBuilder {
+    rects: Vec<Rect>,
+}
+
+impl InitialMapBuilder for BspInteriorBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut rooms: Vec<Rect> = Vec::new();
+        self.rects.clear();
+        self.rects.push(Rect::new(
+            1,
+            1,
+            build_data.map.width - 3,
+            build_data.map.height - 3,
+        ));
+        let first_room = self.rects[0];
+        self.add_subrects(first_room, rng);
+
+        for room in self.rects.clone() {
+            apply_room_to_map(&mut build_data.map, &room);
+            rooms.push(room);
+            build_data.take_snapshot();
+        }
+
+        build_data.rooms = Some(rooms);
+    }
+}
+
+impl BspInteriorBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<BspInteriorBuilder> {
+        Box::new(BspInteriorBuilder { rects: Vec::new() })
+    }
+
+    /// Replaces `rect` with two halves, split along its longer side (or at random when
+    /// it is square), recursing until the pieces are too small to split again.
+    fn add_subrects(&mut self, rect: Rect, rng: &mut RandomNumberGenerator) {
+        if let Some(pos) = self.rects.iter().position(|r| *r == rect) {
+            self.rects.remove(pos);
+        }
+
+        let width = rect.x2 - rect.x1;
+        let height = rect.y2 - rect.y1;
+        let half_width = width / 2;
+        let half_height = height / 2;
+
+        let split_vertically = match width.cmp(&height) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => rng.roll_dice(1, 2) == 1,
+        };
+
+        if split_vertically {
+            let h1 = Rect::new(rect.x1, rect.y1, half_width - 1, height);
+            let h2 = Rect::new(rect.x1 + half_width, rect.y1, half_width, height);
+            self.split_further(h1, half_width, rng);
+            self.split_further(h2, half_width, rng);
+        } else {
+            let v1 = Rect::new(rect.x1, rect.y1, width, half_height - 1);
+            let v2 = Rect::new(rect.x1, rect.y1 + half_height, width, half_height);
+            self.split_further(v1, half_height, rng);
+            self.split_further(v2, half_height, rng);
+        }
+    }
+
+    fn split_further(&mut self, rect: Rect, size: i32, rng: &mut RandomNumberGenerator) {
+        self.rects.push(rect);
+        if size > MIN_ROOM_SIZE {
+            self.add_subrects(rect, rng);
+        }
+    }
+}
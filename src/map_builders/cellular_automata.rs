@@ -0,0 +1,73 @@
+use super::{BuilderMap, InitialMapBuilder, Map, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Caves grown from noise: each pass turns a tile to wall when it is crowded by
+/// walls, or has none nearby, and to floor otherwise.
+pub struct CellularAutomataBuilder {}
+
+impl InitialMapBuilder for CellularAutomataBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.grow_cave(rng, build_data);
+    }
+}
+
+impl CellularAutomataBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<CellularAutomataBuilder> {
+        Box::new(CellularAutomataBuilder {})
+    }
+
+    fn grow_cave(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // Roughly 55% floor to begin with
+        for y in 1..build_data.map.height - 1 {
+            for x in 1..build_data.map.width - 1 {
+                let idx = build_data.map.xy_idx(x, y);
+                build_data.map.tiles[idx] = if rng.roll_dice(1, 100) > 55 {
+                    TileType::Floor
+                } else {
+                    TileType::Wall
+                };
+            }
+        }
+
+        build_data.take_snapshot();
+        for _ in 0..15 {
+            iterate(&mut build_data.map);
+            build_data.take_snapshot();
+        }
+    }
+}
+
+/// One generation: a tile becomes wall when more than four of its eight neighbours are
+/// wall, or none are.
+fn iterate(map: &mut Map) {
+    let mut new_tiles = map.tiles.clone();
+    let width = map.width;
+
+    for y in 1..map.height - 1 {
+        for x in 1..width - 1 {
+            let idx = map.xy_idx(x, y);
+            let neighbors = [
+                idx - 1,
+                idx + 1,
+                idx - width as usize,
+                idx + width as usize,
+                idx - (width as usize - 1),
+                idx - (width as usize + 1),
+                idx + (width as usize - 1),
+                idx + (width as usize + 1),
+            ]
+            .iter()
+            .filter(|i| map.tiles[**i] == TileType::Wall)
+            .count();
+
+            new_tiles[idx] = if neighbors > 4 || neighbors == 0 {
+                TileType::Wall
+            } else {
+                TileType::Floor
+            };
+        }
+    }
+
+    map.tiles = new_tiles;
+}
@@ -0,0 +1,184 @@
+use super::{Map, Position, Rect, TileType};
+use rltk::RandomNumberGenerator;
+use std::cmp::{max, min};
+use std::collections::BTreeMap;
+
+pub fn apply_room_to_map(map: &mut Map, room: &Rect) {
+    for y in room.y1 + 1..=room.y2 {
+        for x in room.x1 + 1..=room.x2 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+}
+
+/// Digs a straight line along x; returns the tiles that were wall.
+pub fn apply_horizontal_tunnel(map: &mut Map, x1: i32, x2: i32, y: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
+    for x in min(x1, x2)..=max(x1, x2) {
+        let idx = map.xy_idx(x, y);
+        if idx > 0 && idx < map.tiles.len() && map.tiles[idx] == TileType::Wall {
+            map.tiles[idx] = TileType::Floor;
+            corridor.push(idx);
+        }
+    }
+    corridor
+}
+
+/// Digs a straight line along y; returns the tiles that were wall.
+pub fn apply_vertical_tunnel(map: &mut Map, y1: i32, y2: i32, x: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
+    for y in min(y1, y2)..=max(y1, y2) {
+        let idx = map.xy_idx(x, y);
+        if idx > 0 && idx < map.tiles.len() && map.tiles[idx] == TileType::Wall {
+            map.tiles[idx] = TileType::Floor;
+            corridor.push(idx);
+        }
+    }
+    corridor
+}
+
+/// Digs a corridor that closes the gap along x first, then along y; returns the
+/// tiles that were wall.
+pub fn draw_corridor(map: &mut Map, x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
+    let (mut x, mut y) = (x1, y1);
+    while x != x2 || y != y2 {
+        if x < x2 {
+            x += 1;
+        } else if x > x2 {
+            x -= 1;
+        } else if y < y2 {
+            y += 1;
+        } else if y > y2 {
+            y -= 1;
+        }
+        let idx = map.xy_idx(x, y);
+        if map.tiles[idx] == TileType::Wall {
+            map.tiles[idx] = TileType::Floor;
+            corridor.push(idx);
+        }
+    }
+    corridor
+}
+
+/// A random point inside a room's floor.
+pub fn random_point_in_room(room: &Rect, rng: &mut RandomNumberGenerator) -> (i32, i32) {
+    (
+        room.x1 + rng.roll_dice(1, room.x2 - room.x1),
+        room.y1 + rng.roll_dice(1, room.y2 - room.y1),
+    )
+}
+
+/// Seals the outer edge so nothing can walk off the map.
+pub fn wall_off_edges(map: &mut Map) {
+    for x in 0..map.width {
+        let top = map.xy_idx(x, 0);
+        let bottom = map.xy_idx(x, map.height - 1);
+        map.tiles[top] = TileType::Wall;
+        map.tiles[bottom] = TileType::Wall;
+    }
+    for y in 0..map.height {
+        let left = map.xy_idx(0, y);
+        let right = map.xy_idx(map.width - 1, y);
+        map.tiles[left] = TileType::Wall;
+        map.tiles[right] = TileType::Wall;
+    }
+}
+
+/// The floor tile closest to the middle of the map, searching leftwards and down.
+/// A map with no floor at all gets its middle tile dug out instead.
+pub fn central_floor(map: &mut Map) -> (i32, i32) {
+    let (mut x, mut y) = (map.width / 2, map.height / 2);
+    // One pass over every tile inside the border
+    for _ in 0..(map.width - 2) * (map.height - 2) {
+        if map.tiles[map.xy_idx(x, y)] == TileType::Floor {
+            return (x, y);
+        }
+        x -= 1;
+        if x < 1 {
+            x = map.width - 2;
+            y = if y + 1 < map.height - 1 { y + 1 } else { 1 };
+        }
+    }
+    let (x, y) = (map.width / 2, map.height / 2);
+    let idx = map.xy_idx(x, y);
+    map.tiles[idx] = TileType::Floor;
+    (x, y)
+}
+
+/// Walls over anything that can't be reached from `start_idx` and returns the
+/// reachable floor tile farthest from it, or `None` if the start is the only floor.
+pub fn remove_unreachable_areas_returning_most_distant(
+    map: &mut Map,
+    start_idx: usize,
+) -> Option<usize> {
+    map.populate_blocked();
+    // No cap on the distance: a winding maze can be longer than any fixed limit
+    let max_depth = map.tiles.len() as f32;
+    let dijkstra = rltk::DijkstraMap::new(map.width, map.height, &[start_idx], map, max_depth);
+
+    let mut exit_tile: Option<(usize, f32)> = None;
+    for (i, tile) in map.tiles.iter_mut().enumerate() {
+        // The start only gets a distance once a path leads back to it, so a start
+        // walled in on every side would otherwise be culled too
+        if *tile != TileType::Wall && i != start_idx {
+            let distance = dijkstra.map[i];
+            if distance == f32::MAX {
+                *tile = TileType::Wall;
+            } else if *tile == TileType::Floor
+                && distance > exit_tile.map_or(0.0, |(_idx, farthest)| farthest)
+            {
+                exit_tile = Some((i, distance));
+            }
+        }
+    }
+    map.populate_blocked();
+    exit_tile.map(|(idx, _distance)| idx)
+}
+
+/// Splits the floor into cellular-noise regions to spread monsters and items over
+/// maps that have no rooms.
+pub fn generate_voronoi_spawn_regions(
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+) -> BTreeMap<i32, Vec<usize>> {
+    let mut noise_areas: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+    let mut noise = rltk::FastNoise::seeded(rng.next_u64());
+    noise.set_noise_type(rltk::NoiseType::Cellular);
+    noise.set_frequency(0.08);
+    noise.set_cellular_distance_function(rltk::CellularDistanceFunction::Manhattan);
+
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let idx = map.xy_idx(x, y);
+            if map.tiles[idx] == TileType::Floor {
+                let cell_value = (noise.get_noise(x as f32, y as f32) * 10240.0) as i32;
+                noise_areas.entry(cell_value).or_default().push(idx);
+            }
+        }
+    }
+    noise_areas
+}
+
+/// The last step of every builder: walls off whatever can't be reached from `start`,
+/// so the whole level is connected, and puts the stairs down as far away as possible
+/// if the level doesn't already have a reachable way down. A start with nowhere to
+/// go gets the stairs dug out right beside it.
+pub fn cull_unreachable_and_place_exit(map: &mut Map, start: &Position) {
+    let start_idx = map.xy_idx(start.x, start.y);
+    let exit_idx = remove_unreachable_areas_returning_most_distant(map, start_idx);
+    if map.tiles.contains(&TileType::DownStairs) {
+        return;
+    }
+    let exit_idx = exit_idx.unwrap_or_else(|| {
+        let (x, y) = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(dx, dy)| (start.x + dx, start.y + dy))
+            .find(|(x, y)| *x > 0 && *x < map.width - 1 && *y > 0 && *y < map.height - 1)
+            .expect("A map needs room for stairs beside the start");
+        map.xy_idx(x, y)
+    });
+    map.tiles[exit_idx] = TileType::DownStairs;
+    map.populate_blocked();
+}
@@ -0,0 +1,58 @@
+use super::{BuilderMap, InitialMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Fraction of the map dug before growth stops.
+const FLOOR_PERCENT: f32 = 0.25;
+
+/// Diffusion-limited aggregation: particles wander in from random spots and stick
+/// where they first bump into the growing cave, giving branching, coral-like tunnels.
+pub struct DlaBuilder {}
+
+impl InitialMapBuilder for DlaBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // A small cross in the middle to grow from
+        let (center_x, center_y) = (build_data.map.width / 2, build_data.map.height / 2);
+        for (dx, dy) in [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let idx = build_data.map.xy_idx(center_x + dx, center_y + dy);
+            build_data.map.tiles[idx] = TileType::Floor;
+        }
+
+        let total_tiles = build_data.map.width * build_data.map.height;
+        let desired_floor_tiles = (FLOOR_PERCENT * total_tiles as f32) as usize;
+        let mut floor_tile_count = 5;
+
+        while floor_tile_count < desired_floor_tiles {
+            let mut x = rng.roll_dice(1, build_data.map.width - 3) + 1;
+            let mut y = rng.roll_dice(1, build_data.map.height - 3) + 1;
+            let (mut prev_x, mut prev_y) = (x, y);
+
+            while build_data.map.tiles[build_data.map.xy_idx(x, y)] == TileType::Wall {
+                prev_x = x;
+                prev_y = y;
+                match rng.roll_dice(1, 4) {
+                    1 if x > 2 => x -= 1,
+                    2 if x < build_data.map.width - 2 => x += 1,
+                    3 if y > 2 => y -= 1,
+                    4 if y < build_data.map.height - 2 => y += 1,
+                    _ => {}
+                }
+            }
+
+            let idx = build_data.map.xy_idx(prev_x, prev_y);
+            if build_data.map.tiles[idx] == TileType::Wall {
+                build_data.map.tiles[idx] = TileType::Floor;
+                floor_tile_count += 1;
+                if floor_tile_count % 10 == 0 {
+                    build_data.take_snapshot();
+                }
+            }
+        }
+    }
+}
+
+impl DlaBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<DlaBuilder> {
+        Box::new(DlaBuilder {})
+    }
+}
@@ -0,0 +1,58 @@
+use super::{BuilderMap, Map, MetaMapBuilder, Rect, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Hangs a door wherever a corridor opens into a room, provided the doorway is a
+/// gap in a straight wall.
+pub struct DoorPlacement {}
+
+impl MetaMapBuilder for DoorPlacement {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let (Some(rooms), Some(corridors)) = (&build_data.rooms, &build_data.corridors) else {
+            return;
+        };
+        let map = &build_data.map;
+        let mut doors: Vec<usize> = Vec::new();
+        for idx in corridors.iter().flatten() {
+            let (x, y) = (*idx as i32 % map.width, *idx as i32 / map.width);
+            let at_room = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .any(|(dx, dy)| rooms.iter().any(|room| in_room(room, x + dx, y + dy)));
+            let next_to_door = doors.iter().any(|door| {
+                let (door_x, door_y) = (*door as i32 % map.width, *door as i32 / map.width);
+                i32::abs(door_x - x) <= 1 && i32::abs(door_y - y) <= 1
+            });
+            if at_room
+                && !next_to_door
+                && !rooms.iter().any(|room| in_room(room, x, y))
+                && door_possible(map, x, y)
+            {
+                doors.push(*idx);
+            }
+        }
+        for door in doors {
+            build_data.spawn_list.push((door, "Door".to_string()));
+        }
+    }
+}
+
+impl DoorPlacement {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<DoorPlacement> {
+        Box::new(DoorPlacement {})
+    }
+}
+
+fn in_room(room: &Rect, x: i32, y: i32) -> bool {
+    x > room.x1 && x <= room.x2 && y > room.y1 && y <= room.y2
+}
+
+/// Floor on the two sides a door swings between and wall on the other two.
+fn door_possible(map: &Map, x: i32, y: i32) -> bool {
+    if map.tiles[map.xy_idx(x, y)] != TileType::Floor {
+        return false;
+    }
+    let is_floor = |dx: i32, dy: i32| map.tiles[map.xy_idx(x + dx, y + dy)] == TileType::Floor;
+    let is_wall = |dx: i32, dy: i32| map.tiles[map.xy_idx(x + dx, y + dy)] == TileType::Wall;
+    (is_floor(-1, 0) && is_floor(1, 0) && is_wall(0, -1) && is_wall(0, 1))
+        || (is_wall(-1, 0) && is_wall(1, 0) && is_floor(0, -1) && is_floor(0, 1))
+}
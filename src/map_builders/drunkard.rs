@@ -0,0 +1,67 @@
+use super::{BuilderMap, InitialMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Fraction of the map dug before the diggers stop.
+const FLOOR_PERCENT: f32 = 0.5;
+/// How many steps a digger takes before giving up.
+const DRUNKEN_LIFETIME: i32 = 400;
+
+/// Winding caves dug by diggers that stagger about at random, each setting off from
+/// somewhere already dug.
+pub struct DrunkardsWalkBuilder {}
+
+impl InitialMapBuilder for DrunkardsWalkBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let start = (build_data.map.width / 2, build_data.map.height / 2);
+        let start_idx = build_data.map.xy_idx(start.0, start.1);
+        build_data.map.tiles[start_idx] = TileType::Floor;
+
+        let total_tiles = build_data.map.width * build_data.map.height;
+        let desired_floor_tiles = (FLOOR_PERCENT * total_tiles as f32) as usize;
+        let mut floor_tile_count = 1;
+        let mut digger_count = 0;
+
+        while floor_tile_count < desired_floor_tiles {
+            // The first digger starts in the middle, the rest on any dug tile
+            let (mut x, mut y) = if digger_count == 0 {
+                start
+            } else {
+                let floors: Vec<usize> = build_data
+                    .map
+                    .tiles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_idx, tile)| **tile == TileType::Floor)
+                    .map(|(idx, _tile)| idx)
+                    .collect();
+                let idx = floors[(rng.roll_dice(1, floors.len() as i32) - 1) as usize] as i32;
+                (idx % build_data.map.width, idx / build_data.map.width)
+            };
+
+            for _ in 0..DRUNKEN_LIFETIME {
+                let idx = build_data.map.xy_idx(x, y);
+                if build_data.map.tiles[idx] == TileType::Wall {
+                    build_data.map.tiles[idx] = TileType::Floor;
+                    floor_tile_count += 1;
+                }
+
+                match rng.roll_dice(1, 4) {
+                    1 if x > 2 => x -= 1,
+                    2 if x < build_data.map.width - 2 => x += 1,
+                    3 if y > 2 => y -= 1,
+                    4 if y < build_data.map.height - 2 => y += 1,
+                    _ => {}
+                }
+            }
+            digger_count += 1;
+            build_data.take_snapshot();
+        }
+    }
+}
+
+impl DrunkardsWalkBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<DrunkardsWalkBuilder> {
+        Box::new(DrunkardsWalkBuilder {})
+    }
+}
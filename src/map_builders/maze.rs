@@ -0,0 +1,64 @@
+use super::{BuilderMap, InitialMapBuilder, Map, TileType};
+use rltk::RandomNumberGenerator;
+
+/// A perfect maze carved by a recursive backtracker: every cell is reachable by
+/// exactly one path.
+pub struct MazeBuilder {}
+
+impl InitialMapBuilder for MazeBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // Cells sit on odd coordinates, leaving walls between them to knock through
+        let cells_wide = (build_data.map.width - 1) / 2;
+        let cells_high = (build_data.map.height - 1) / 2;
+        let cell_idx = |x: i32, y: i32| (y * cells_wide + x) as usize;
+        let mut visited = vec![false; (cells_wide * cells_high) as usize];
+        let mut backtrace: Vec<(i32, i32)> = vec![(0, 0)];
+        visited[0] = true;
+        dig(&mut build_data.map, 0, 0);
+        let mut cells_dug = 1;
+
+        while let Some(&(x, y)) = backtrace.last() {
+            let neighbors: Vec<(i32, i32)> = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|(nx, ny)| {
+                    *nx >= 0
+                        && *ny >= 0
+                        && *nx < cells_wide
+                        && *ny < cells_high
+                        && !visited[cell_idx(*nx, *ny)]
+                })
+                .collect();
+
+            if neighbors.is_empty() {
+                backtrace.pop();
+                continue;
+            }
+
+            let (next_x, next_y) =
+                neighbors[(rng.roll_dice(1, neighbors.len() as i32) - 1) as usize];
+            visited[cell_idx(next_x, next_y)] = true;
+            dig(&mut build_data.map, next_x, next_y);
+            // Knock through the wall between the two cells
+            let wall_idx = build_data.map.xy_idx(x + next_x + 1, y + next_y + 1);
+            build_data.map.tiles[wall_idx] = TileType::Floor;
+            backtrace.push((next_x, next_y));
+            cells_dug += 1;
+            if cells_dug % 10 == 0 {
+                build_data.take_snapshot();
+            }
+        }
+    }
+}
+
+impl MazeBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<MazeBuilder> {
+        Box::new(MazeBuilder {})
+    }
+}
+
+fn dig(map: &mut Map, cell_x: i32, cell_y: i32) {
+    let idx = map.xy_idx(cell_x * 2 + 1, cell_y * 2 + 1);
+    map.tiles[idx] = TileType::Floor;
+}
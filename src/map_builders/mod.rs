@@ -0,0 +1,363 @@
+use super::{spawner, Map, Position, Rect, TileType};
+use rltk::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+mod common;
+use common::*;
+mod bsp_dungeon;
+mod bsp_interior;
+mod cellular_automata;
+mod dla;
+mod door_placement;
+mod drunkard;
+mod maze;
+mod prefab_builder;
+mod room_corner_rounding;
+mod room_corridors_bsp;
+mod room_corridors_dijkstra;
+mod room_corridors_dogleg;
+mod room_corridors_nearest;
+mod room_exploder;
+mod simple_map;
+mod voronoi;
+mod waveform_collapse;
+pub use bsp_dungeon::BspDungeonBuilder;
+pub use bsp_interior::BspInteriorBuilder;
+pub use cellular_automata::CellularAutomataBuilder;
+pub use dla::DlaBuilder;
+pub use door_placement::DoorPlacement;
+pub use drunkard::DrunkardsWalkBuilder;
+pub use maze::MazeBuilder;
+pub use prefab_builder::{
+    load_rex_prefab, HorizontalPlacement, PrefabBuilder, PrefabLevel, PrefabSection, RexPrefab,
+    VerticalPlacement, CRYPT, GATEHOUSE,
+};
+pub use room_corner_rounding::RoomCornerRounder;
+pub use room_corridors_bsp::BspCorridors;
+pub use room_corridors_dijkstra::DijkstraCorridors;
+pub use room_corridors_dogleg::DoglegCorridors;
+pub use room_corridors_nearest::NearestCorridors;
+pub use room_exploder::RoomExploder;
+pub use simple_map::SimpleMapBuilder;
+pub use voronoi::VoronoiCellBuilder;
+pub use waveform_collapse::WaveformCollapseBuilder;
+
+/// Everything a chain of builders works on together.
+pub struct BuilderMap {
+    /// Entities to create once the map is in place, by tile index and name.
+    pub spawn_list: Vec<(usize, String)>,
+    pub map: Map,
+    pub starting_position: Option<Position>,
+    /// Set by builders that dig rooms; `None` for caves and the like.
+    pub rooms: Option<Vec<Rect>>,
+    /// The tiles dug for each corridor, set by the corridor builders.
+    pub corridors: Option<Vec<Vec<usize>>>,
+    /// The tiles as they stood after each step, oldest first.
+    pub history: Vec<Vec<TileType>>,
+}
+
+impl BuilderMap {
+    /// Records the tiles as they are now, for the generation visualizer.
+    pub fn take_snapshot(&mut self) {
+        self.history.push(self.map.tiles.clone());
+    }
+
+    /// Where the player starts, settled the first time it is asked for: the middle of
+    /// the first room, or the floor nearest the middle of a room-less map.
+    pub fn start(&mut self) -> Position {
+        if let Some(start) = &self.starting_position {
+            return start.clone();
+        }
+        let (x, y) = match &self.rooms {
+            Some(rooms) if !rooms.is_empty() => rooms[0].center(),
+            _ => {
+                wall_off_edges(&mut self.map);
+                central_floor(&mut self.map)
+            }
+        };
+        self.starting_position = Some(Position { x, y });
+        Position { x, y }
+    }
+}
+
+/// Lays down the first draft of a map.
+pub trait InitialMapBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap);
+}
+
+/// Reworks a map some earlier builder has laid down. Builders that need rooms leave
+/// room-less maps alone.
+pub trait MetaMapBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap);
+}
+
+/// An initial builder followed by any number of meta-builders, run in order. Every
+/// chain ends the same way: unreachable floor is walled over and, unless a prefab
+/// drew its own, the stairs down go on the reachable tile farthest from the start.
+pub struct BuilderChain {
+    starter: Option<Box<dyn InitialMapBuilder>>,
+    builders: Vec<Box<dyn MetaMapBuilder>>,
+    pub build_data: BuilderMap,
+}
+
+impl BuilderChain {
+    pub fn new(width: i32, height: i32, depth: i32) -> BuilderChain {
+        let mut map = Map::new(width, height);
+        map.depth = depth;
+        BuilderChain {
+            starter: None,
+            builders: Vec::new(),
+            build_data: BuilderMap {
+                spawn_list: Vec::new(),
+                map,
+                starting_position: None,
+                rooms: None,
+                corridors: None,
+                history: Vec::new(),
+            },
+        }
+    }
+
+    pub fn start_with(mut self, starter: Box<dyn InitialMapBuilder>) -> BuilderChain {
+        self.starter = Some(starter);
+        self
+    }
+
+    pub fn with(mut self, metabuilder: Box<dyn MetaMapBuilder>) -> BuilderChain {
+        self.builders.push(metabuilder);
+        self
+    }
+
+    pub fn build(&mut self, rng: &mut RandomNumberGenerator) {
+        let starter = self
+            .starter
+            .as_mut()
+            .expect("A builder chain needs an initial builder");
+        starter.build_map(rng, &mut self.build_data);
+        for metabuilder in self.builders.iter_mut() {
+            metabuilder.build_map(rng, &mut self.build_data);
+        }
+
+        let start = self.build_data.start();
+        let map = &mut self.build_data.map;
+        wall_off_edges(map);
+        cull_unreachable_and_place_exit(map, &start);
+        let map = &self.build_data.map;
+        self.build_data
+            .spawn_list
+            .retain(|(idx, _name)| map.tiles[*idx] == TileType::Floor);
+        self.build_data.take_snapshot();
+    }
+
+    pub fn get_map(&self) -> Map {
+        let mut map = self.build_data.map.clone();
+        map.rooms = self.build_data.rooms.clone().unwrap_or_default();
+        map
+    }
+
+    pub fn get_starting_position(&self) -> Position {
+        self.build_data
+            .starting_position
+            .clone()
+            .expect("The chain has not been built")
+    }
+
+    pub fn get_snapshot_history(&self) -> Vec<Vec<TileType>> {
+        self.build_data.history.clone()
+    }
+
+    /// Populates the level; the map must already be the `Map` resource. The spawn list
+    /// goes first, then rooms (or regions, on maps without rooms) are filled at random.
+    pub fn spawn_entities(&mut self, ecs: &mut World) {
+        for (idx, name) in self.build_data.spawn_list.iter() {
+            spawner::spawn_named(ecs, *idx, name);
+        }
+        let taken: Vec<usize> = self
+            .build_data
+            .spawn_list
+            .iter()
+            .map(|(idx, _name)| *idx)
+            .collect();
+        match &self.build_data.rooms {
+            Some(rooms) => spawner::spawn_rooms(ecs, rooms, &taken),
+            None => {
+                let start = self.get_starting_position();
+                spawn_regions(ecs, &start, &taken);
+            }
+        }
+    }
+}
+
+/// The first step of a chain, as data.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum InitialStep {
+    SimpleMap,
+    BspDungeon,
+    BspInterior,
+    CellularAutomata,
+    DrunkardsWalk,
+    Maze,
+    Dla,
+    Voronoi,
+    /// The hand-drawn crypt.
+    PrefabLevel,
+}
+
+/// A later step of a chain, as data.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum MetaStep {
+    RoomExploder,
+    RoundedRoomCorners,
+    DoglegCorridors,
+    BspCorridors,
+    NearestCorridors,
+    DijkstraCorridors,
+    Doors,
+    Vaults,
+    WaveformCollapse,
+    /// The hand-drawn gatehouse, against the right-hand edge.
+    PrefabSection,
+}
+
+/// A whole builder chain written down as data, so level themes can be stored, mixed
+/// and matched.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct ChainSpec {
+    pub start: InitialStep,
+    pub steps: Vec<MetaStep>,
+}
+
+impl ChainSpec {
+    /// Rooms joined one after the other by L-shaped corridors.
+    pub fn classic() -> ChainSpec {
+        ChainSpec {
+            start: InitialStep::SimpleMap,
+            steps: vec![MetaStep::DoglegCorridors],
+        }
+    }
+
+    pub fn builder(&self, width: i32, height: i32, depth: i32) -> BuilderChain {
+        let starter: Box<dyn InitialMapBuilder> = match self.start {
+            InitialStep::SimpleMap => SimpleMapBuilder::new(),
+            InitialStep::BspDungeon => BspDungeonBuilder::new(),
+            InitialStep::BspInterior => BspInteriorBuilder::new(),
+            InitialStep::CellularAutomata => CellularAutomataBuilder::new(),
+            InitialStep::DrunkardsWalk => DrunkardsWalkBuilder::new(),
+            InitialStep::Maze => MazeBuilder::new(),
+            InitialStep::Dla => DlaBuilder::new(),
+            InitialStep::Voronoi => VoronoiCellBuilder::new(),
+            InitialStep::PrefabLevel => PrefabBuilder::level(CRYPT),
+        };
+        let mut chain = BuilderChain::new(width, height, depth).start_with(starter);
+        for step in self.steps.iter() {
+            let metabuilder: Box<dyn MetaMapBuilder> = match step {
+                MetaStep::RoomExploder => RoomExploder::new(),
+                MetaStep::RoundedRoomCorners => RoomCornerRounder::new(),
+                MetaStep::DoglegCorridors => DoglegCorridors::new(),
+                MetaStep::BspCorridors => BspCorridors::new(),
+                MetaStep::NearestCorridors => NearestCorridors::new(),
+                MetaStep::DijkstraCorridors => DijkstraCorridors::new(),
+                MetaStep::Doors => DoorPlacement::new(),
+                MetaStep::Vaults => PrefabBuilder::vaults(),
+                MetaStep::WaveformCollapse => WaveformCollapseBuilder::new(),
+                MetaStep::PrefabSection => PrefabBuilder::sectional(GATEHOUSE),
+            };
+            chain = chain.with(metabuilder);
+        }
+        chain
+    }
+}
+
+/// The first level is always the classic rooms and corridors; deeper ones mix any
+/// initial builder with whatever meta-builders suit it.
+pub fn random_spec(depth: i32, rng: &mut RandomNumberGenerator) -> ChainSpec {
+    if depth <= 1 {
+        return ChainSpec::classic();
+    }
+    let (start, mut steps) = match rng.roll_dice(1, 10) {
+        1 => (InitialStep::SimpleMap, Vec::new()),
+        2 => (InitialStep::BspDungeon, Vec::new()),
+        3 => (InitialStep::BspInterior, Vec::new()),
+        4 => (InitialStep::CellularAutomata, Vec::new()),
+        5 => (InitialStep::DrunkardsWalk, Vec::new()),
+        6 => (InitialStep::Maze, Vec::new()),
+        7 => (InitialStep::Dla, Vec::new()),
+        8 => (InitialStep::Voronoi, Vec::new()),
+        9 => (
+            InitialStep::CellularAutomata,
+            vec![MetaStep::WaveformCollapse],
+        ),
+        _ => (InitialStep::PrefabLevel, Vec::new()),
+    };
+
+    if matches!(
+        start,
+        InitialStep::SimpleMap | InitialStep::BspDungeon | InitialStep::BspInterior
+    ) {
+        // Rooms of a BSP interior share their walls, so there is nothing to reshape
+        if start != InitialStep::BspInterior {
+            match rng.roll_dice(1, 4) {
+                1 => steps.push(MetaStep::RoomExploder),
+                2 => steps.push(MetaStep::RoundedRoomCorners),
+                _ => {}
+            }
+        }
+        steps.push(match rng.roll_dice(1, 4) {
+            1 => MetaStep::DoglegCorridors,
+            2 => MetaStep::BspCorridors,
+            3 => MetaStep::NearestCorridors,
+            _ => MetaStep::DijkstraCorridors,
+        });
+        if rng.roll_dice(1, 2) == 1 {
+            steps.push(MetaStep::Doors);
+        }
+    }
+    // Open caves have room for the gatehouse without cutting through anything
+    if matches!(
+        start,
+        InitialStep::CellularAutomata
+            | InitialStep::DrunkardsWalk
+            | InitialStep::Dla
+            | InitialStep::Voronoi
+    ) && steps.is_empty()
+        && rng.roll_dice(1, 4) == 1
+    {
+        steps.push(MetaStep::PrefabSection);
+    }
+    if rng.roll_dice(1, 3) == 1 {
+        steps.push(MetaStep::Vaults);
+    }
+    ChainSpec { start, steps }
+}
+
+pub fn random_builder(
+    width: i32,
+    height: i32,
+    depth: i32,
+    rng: &mut RandomNumberGenerator,
+) -> BuilderChain {
+    random_spec(depth, rng).builder(width, height, depth)
+}
+
+/// Fills the regions of a room-less map, leaving the one the player starts in free
+/// of monsters. Works from the `Map` resource rather than the builder's copy, so the
+/// stairs the player arrives on are no longer floor.
+fn spawn_regions(ecs: &mut World, start: &Position, taken: &[usize]) {
+    let (regions, start_idx) = {
+        let map = ecs.fetch::<Map>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        (
+            generate_voronoi_spawn_regions(&map, &mut rng),
+            map.xy_idx(start.x, start.y),
+        )
+    };
+    for area in regions.values() {
+        let free: Vec<usize> = area
+            .iter()
+            .copied()
+            .filter(|idx| !taken.contains(idx))
+            .collect();
+        spawner::spawn_region(ecs, &free, !area.contains(&start_idx));
+    }
+}
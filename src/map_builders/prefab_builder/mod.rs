@@ -0,0 +1,231 @@
+use super::{BuilderMap, InitialMapBuilder, MetaMapBuilder, Position, Rect, TileType};
+use rltk::RandomNumberGenerator;
+use std::collections::HashSet;
+
+mod prefab_levels;
+mod prefab_rooms;
+mod prefab_sections;
+mod rex;
+pub use prefab_levels::*;
+use prefab_rooms::*;
+pub use prefab_sections::*;
+pub use rex::*;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum PrefabMode {
+    /// Lays down a whole hand-drawn level.
+    RexLevel { level: PrefabLevel },
+    /// Stamps a hand-drawn piece over part of the map.
+    Sectional { section: PrefabSection },
+    /// Stamps up to three vaults fit for the depth onto open floor.
+    RoomVaults,
+}
+
+/// Builds from hand-designed pieces rather than an algorithm.
+pub struct PrefabBuilder {
+    mode: PrefabMode,
+}
+
+impl InitialMapBuilder for PrefabBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl MetaMapBuilder for PrefabBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl PrefabBuilder {
+    pub fn level(level: PrefabLevel) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::RexLevel { level },
+        })
+    }
+
+    pub fn sectional(section: PrefabSection) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::Sectional { section },
+        })
+    }
+
+    pub fn vaults() -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::RoomVaults,
+        })
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        match self.mode {
+            PrefabMode::RexLevel { level } => self.load_rex_level(level, build_data),
+            PrefabMode::Sectional { section } => self.apply_sectional(section, build_data),
+            PrefabMode::RoomVaults => self.apply_room_vaults(rng, build_data),
+        }
+    }
+
+    /// Sets the tile at `idx` from a template character, queueing anything that
+    /// should stand there. Characters it doesn't know are left as floor.
+    fn char_to_map(&self, ch: char, idx: usize, build_data: &mut BuilderMap) {
+        let spawn = match ch {
+            '#' | '█' => {
+                build_data.map.tiles[idx] = TileType::Wall;
+                return;
+            }
+            '>' => {
+                build_data.map.tiles[idx] = TileType::DownStairs;
+                return;
+            }
+            '@' => {
+                let width = build_data.map.width;
+                build_data.starting_position = Some(Position {
+                    x: idx as i32 % width,
+                    y: idx as i32 / width,
+                });
+                None
+            }
+            'g' => Some("Goblin"),
+            'o' => Some("Orc"),
+            '!' => Some("Health Potion"),
+            '?' => Some("Magic Missile Scroll"),
+            '/' => Some("Dagger"),
+            '[' => Some("Shield"),
+            '+' => Some("Door"),
+            _ => None,
+        };
+        build_data.map.tiles[idx] = TileType::Floor;
+        if let Some(name) = spawn {
+            build_data.spawn_list.push((idx, name.to_string()));
+        }
+    }
+
+    fn load_rex_level(&mut self, level: PrefabLevel, build_data: &mut BuilderMap) {
+        let prefab = load_rex_prefab(level.template);
+        let x = (build_data.map.width - prefab.width as i32) / 2;
+        let y = (build_data.map.height - prefab.height as i32) / 2;
+        self.stamp(&prefab, x, y, build_data);
+        build_data.take_snapshot();
+    }
+
+    /// Stamps the section over the map. Spawns and rooms it covers go, and so does
+    /// the start if it had been settled inside.
+    fn apply_sectional(&mut self, section: PrefabSection, build_data: &mut BuilderMap) {
+        let prefab = load_rex_prefab(section.template);
+        let (width, height) = (prefab.width as i32, prefab.height as i32);
+        let x = match section.placement.0 {
+            HorizontalPlacement::Left => 1,
+            HorizontalPlacement::Center => (build_data.map.width - width) / 2,
+            HorizontalPlacement::Right => build_data.map.width - 1 - width,
+        };
+        let y = match section.placement.1 {
+            VerticalPlacement::Top => 1,
+            VerticalPlacement::Center => (build_data.map.height - height) / 2,
+            VerticalPlacement::Bottom => build_data.map.height - 1 - height,
+        };
+
+        let area = Rect::new(x, y, width - 1, height - 1);
+        let inside = |pos_x: i32, pos_y: i32| {
+            pos_x >= area.x1 && pos_x <= area.x2 && pos_y >= area.y1 && pos_y <= area.y2
+        };
+        let map_width = build_data.map.width;
+        build_data
+            .spawn_list
+            .retain(|(idx, _name)| !inside(*idx as i32 % map_width, *idx as i32 / map_width));
+        if let Some(rooms) = build_data.rooms.as_mut() {
+            rooms.retain(|room| !room.intersect(&area));
+        }
+        if let Some(start) = &build_data.starting_position {
+            if inside(start.x, start.y) {
+                build_data.starting_position = None;
+            }
+        }
+
+        self.stamp(&prefab, x, y, build_data);
+        build_data.take_snapshot();
+    }
+
+    /// Copies a prefab onto the map with its top-left corner at `(x, y)`, leaving
+    /// off whatever falls outside.
+    fn stamp(&self, prefab: &RexPrefab, x: i32, y: i32, build_data: &mut BuilderMap) {
+        for ty in 0..prefab.height {
+            for tx in 0..prefab.width {
+                let (map_x, map_y) = (x + tx as i32, y + ty as i32);
+                if build_data.map.in_bounds(map_x, map_y) {
+                    let idx = build_data.map.xy_idx(map_x, map_y);
+                    self.char_to_map(prefab.glyphs[tx + ty * prefab.width], idx, build_data);
+                }
+            }
+        }
+    }
+
+    fn apply_room_vaults(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let start = build_data.start();
+        let start_idx = build_data.map.xy_idx(start.x, start.y);
+        let depth = build_data.map.depth;
+        let mut possible_vaults: Vec<PrefabRoom> =
+            [GUARD_POST, PILLARED_HALL, GOBLIN_SHRINE, ARMOURY]
+                .into_iter()
+                .filter(|vault| depth >= vault.first_depth && depth <= vault.last_depth)
+                .collect();
+        if possible_vaults.is_empty() {
+            return;
+        }
+
+        let n_vaults = i32::min(rng.roll_dice(1, 3), possible_vaults.len() as i32);
+        let mut used_tiles: HashSet<usize> = HashSet::new();
+        for _ in 0..n_vaults {
+            let vault_index = (rng.roll_dice(1, possible_vaults.len() as i32) - 1) as usize;
+            let vault = possible_vaults.remove(vault_index);
+            let (width, height) = (vault.width as i32, vault.height as i32);
+
+            // Anywhere the whole vault lands on untouched floor, clear of the start
+            let map = &build_data.map;
+            let mut vault_positions: Vec<(i32, i32)> = Vec::new();
+            for y in 1..map.height - height {
+                for x in 1..map.width - width {
+                    let fits = (0..height).all(|ty| {
+                        (0..width).all(|tx| {
+                            let idx = map.xy_idx(x + tx, y + ty);
+                            map.tiles[idx] == TileType::Floor
+                                && idx != start_idx
+                                && !used_tiles.contains(&idx)
+                        })
+                    });
+                    if fits {
+                        vault_positions.push((x, y));
+                    }
+                }
+            }
+            if vault_positions.is_empty() {
+                continue;
+            }
+
+            let pos_index = (rng.roll_dice(1, vault_positions.len() as i32) - 1) as usize;
+            let (x, y) = vault_positions[pos_index];
+            let map_width = build_data.map.width;
+            build_data.spawn_list.retain(|(idx, _name)| {
+                let (spawn_x, spawn_y) = (*idx as i32 % map_width, *idx as i32 / map_width);
+                spawn_x < x || spawn_x >= x + width || spawn_y < y || spawn_y >= y + height
+            });
+            let template = vault.template.glyphs();
+            for ty in 0..height {
+                for tx in 0..width {
+                    let idx = build_data.map.xy_idx(x + tx, y + ty);
+                    let ch = template[tx as usize + ty as usize * vault.width];
+                    self.char_to_map(ch, idx, build_data);
+                    used_tiles.insert(idx);
+                }
+            }
+            build_data.take_snapshot();
+        }
+    }
+}
+
+/// A template's characters row by row, line breaks dropped.
+fn read_template(template: &str) -> Vec<char> {
+    template
+        .chars()
+        .filter(|ch| *ch != '\r' && *ch != '\n')
+        .collect()
+}
@@ -0,0 +1,11 @@
+/// A whole level drawn in REX Paint, centred on the map with solid rock around it.
+/// `@` marks where the player starts; `>` is the way down.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct PrefabLevel {
+    pub template: &'static [u8],
+}
+
+/// Four rows of burial halls, joined by doors and gaps in the walls.
+pub const CRYPT: PrefabLevel = PrefabLevel {
+    template: include_bytes!("../../../resources/crypt.xp"),
+};
@@ -0,0 +1,87 @@
+use super::{load_rex_prefab, read_template};
+
+/// A hand-drawn vault, stamped onto open floor. `#` is wall, a space is floor and
+/// letters and symbols are things to spawn there (see `PrefabBuilder::char_to_map`).
+/// Each template keeps a ring of floor around its walls so it never seals a passage.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct PrefabRoom {
+    pub template: PrefabTemplate,
+    pub width: usize,
+    pub height: usize,
+    pub first_depth: i32,
+    pub last_depth: i32,
+}
+
+/// Where a vault's drawing comes from: a string in this file or a REX Paint file.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum PrefabTemplate {
+    Text(&'static str),
+    Rex(&'static [u8]),
+}
+
+impl PrefabTemplate {
+    pub fn glyphs(&self) -> Vec<char> {
+        match self {
+            PrefabTemplate::Text(template) => read_template(template),
+            PrefabTemplate::Rex(bytes) => load_rex_prefab(bytes).glyphs,
+        }
+    }
+}
+
+pub const GUARD_POST: PrefabRoom = PrefabRoom {
+    template: PrefabTemplate::Text(GUARD_POST_MAP),
+    width: 7,
+    height: 5,
+    first_depth: 2,
+    last_depth: 100,
+};
+
+const GUARD_POST_MAP: &str = "
+       
+ ##### 
+ #!o!# 
+ ## ## 
+       
+";
+
+pub const PILLARED_HALL: PrefabRoom = PrefabRoom {
+    template: PrefabTemplate::Text(PILLARED_HALL_MAP),
+    width: 7,
+    height: 5,
+    first_depth: 2,
+    last_depth: 100,
+};
+
+const PILLARED_HALL_MAP: &str = "
+       
+ #   # 
+   !   
+ #   # 
+       
+";
+
+pub const GOBLIN_SHRINE: PrefabRoom = PrefabRoom {
+    template: PrefabTemplate::Text(GOBLIN_SHRINE_MAP),
+    width: 9,
+    height: 7,
+    first_depth: 3,
+    last_depth: 100,
+};
+
+const GOBLIN_SHRINE_MAP: &str = "
+         
+ ####### 
+ #! ! !# 
+ #     # 
+ #g   g# 
+ ### ### 
+         
+";
+
+pub const ARMOURY: PrefabRoom = PrefabRoom {
+    template: PrefabTemplate::Rex(include_bytes!("../../../resources/armoury.xp")),
+    width: 7,
+    height: 6,
+    first_depth: 3,
+    last_depth: 100,
+};
@@ -0,0 +1,27 @@
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum HorizontalPlacement {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum VerticalPlacement {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Part of a level drawn in REX Paint and stamped over whatever a builder dug, in
+/// one of nine spots against the edges or the middle of the map.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct PrefabSection {
+    pub template: &'static [u8],
+    pub placement: (HorizontalPlacement, VerticalPlacement),
+}
+
+/// A walled keep with a gate on every side, guarded by orcs.
+pub const GATEHOUSE: PrefabSection = PrefabSection {
+    template: include_bytes!("../../../resources/gatehouse.xp"),
+    placement: (HorizontalPlacement::Right, VerticalPlacement::Center),
+};
@@ -0,0 +1,40 @@
+use rltk::rex::XpFile;
+
+/// A REX Paint drawing flattened to one layer of characters.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RexPrefab {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row, as `read_template` gives them for text templates.
+    pub glyphs: Vec<char>,
+}
+
+/// Reads an `.xp` file. Layers are stacked in order, so markers can be drawn on a
+/// layer above the walls; transparent cells let the layers below show through and
+/// are floor if nothing is drawn under them. Glyphs are translated from code page
+/// 437, so REX Paint's solid block reads as `█`.
+pub fn load_rex_prefab(bytes: &[u8]) -> RexPrefab {
+    let xp = XpFile::read(&mut &bytes[..]).expect("Unable to read REX Paint file");
+    let (width, height) = xp
+        .layers
+        .first()
+        .map(|layer| (layer.width, layer.height))
+        .unwrap_or((0, 0));
+    let mut glyphs = vec![' '; width * height];
+    for layer in xp.layers.iter() {
+        for y in 0..height {
+            for x in 0..width {
+                if let Some(cell) = layer.get(x, y) {
+                    if !cell.bg.is_transparent() {
+                        glyphs[x + y * width] = rltk::to_char(cell.ch as u8);
+                    }
+                }
+            }
+        }
+    }
+    RexPrefab {
+        width,
+        height,
+        glyphs,
+    }
+}
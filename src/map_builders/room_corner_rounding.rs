@@ -0,0 +1,40 @@
+use super::{BuilderMap, Map, MetaMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Knocks the corners off rectangular rooms by walling in each corner tile that is
+/// already hemmed in on two sides.
+pub struct RoomCornerRounder {}
+
+impl MetaMapBuilder for RoomCornerRounder {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        for room in rooms.iter() {
+            let map = &mut build_data.map;
+            fill_if_corner(map, room.x1 + 1, room.y1 + 1);
+            fill_if_corner(map, room.x2, room.y1 + 1);
+            fill_if_corner(map, room.x1 + 1, room.y2);
+            fill_if_corner(map, room.x2, room.y2);
+            build_data.take_snapshot();
+        }
+    }
+}
+
+impl RoomCornerRounder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<RoomCornerRounder> {
+        Box::new(RoomCornerRounder {})
+    }
+}
+
+fn fill_if_corner(map: &mut Map, x: i32, y: i32) {
+    let walls = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .iter()
+        .filter(|(dx, dy)| map.tiles[map.xy_idx(x + dx, y + dy)] == TileType::Wall)
+        .count();
+    if walls == 2 {
+        let idx = map.xy_idx(x, y);
+        map.tiles[idx] = TileType::Wall;
+    }
+}
@@ -0,0 +1,30 @@
+use super::{draw_corridor, random_point_in_room, BuilderMap, MetaMapBuilder};
+use rltk::RandomNumberGenerator;
+
+/// Joins each room to the one listed before it, from a random spot in one to a
+/// random spot in the other.
+pub struct BspCorridors {}
+
+impl MetaMapBuilder for BspCorridors {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        let mut corridors = Vec::new();
+        for pair in rooms.windows(2) {
+            let (start_x, start_y) = random_point_in_room(&pair[0], rng);
+            let (end_x, end_y) = random_point_in_room(&pair[1], rng);
+            let corridor = draw_corridor(&mut build_data.map, start_x, start_y, end_x, end_y);
+            build_data.take_snapshot();
+            corridors.push(corridor);
+        }
+        build_data.corridors = Some(corridors);
+    }
+}
+
+impl BspCorridors {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<BspCorridors> {
+        Box::new(BspCorridors {})
+    }
+}
@@ -0,0 +1,101 @@
+use super::{BuilderMap, Map, MetaMapBuilder, Rect, TileType};
+use rltk::RandomNumberGenerator;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// What it costs a corridor to dig through a wall tile, against one to follow floor.
+const DIG_COST: u32 = 4;
+
+/// Joins each room, in order, to the network built so far by the cheapest path, found
+/// by Dijkstra's algorithm. Following floor costs less than digging, so corridors
+/// merge into existing ones and cut through rooms rather than running alongside them.
+pub struct DijkstraCorridors {}
+
+impl MetaMapBuilder for DijkstraCorridors {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        let mut network = vec![false; build_data.map.tiles.len()];
+        let mut corridors = Vec::new();
+        for (i, room) in rooms.iter().enumerate() {
+            if i > 0 {
+                let (x, y) = room.center();
+                let start = build_data.map.xy_idx(x, y);
+                if let Some(path) = cheapest_path(&build_data.map, start, &network) {
+                    let mut corridor = Vec::new();
+                    for idx in path {
+                        network[idx] = true;
+                        if build_data.map.tiles[idx] == TileType::Wall {
+                            build_data.map.tiles[idx] = TileType::Floor;
+                            corridor.push(idx);
+                        }
+                    }
+                    build_data.take_snapshot();
+                    corridors.push(corridor);
+                }
+            }
+            mark_room(&build_data.map, room, &mut network);
+        }
+        build_data.corridors = Some(corridors);
+    }
+}
+
+impl DijkstraCorridors {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<DijkstraCorridors> {
+        Box::new(DijkstraCorridors {})
+    }
+}
+
+fn mark_room(map: &Map, room: &Rect, network: &mut [bool]) {
+    for y in room.y1 + 1..=room.y2 {
+        for x in room.x1 + 1..=room.x2 {
+            network[map.xy_idx(x, y)] = true;
+        }
+    }
+}
+
+/// The tiles from `start` to the nearest tile of `network`, moving orthogonally and
+/// never onto the map's edge; `None` if the network can't be reached at all.
+fn cheapest_path(map: &Map, start: usize, network: &[bool]) -> Option<Vec<usize>> {
+    let mut cost = vec![u32::MAX; map.tiles.len()];
+    let mut came_from = vec![usize::MAX; map.tiles.len()];
+    let mut frontier = BinaryHeap::new();
+    cost[start] = 0;
+    frontier.push(Reverse((0, start)));
+
+    while let Some(Reverse((here_cost, here))) = frontier.pop() {
+        if network[here] {
+            let mut path = vec![here];
+            let mut idx = here;
+            while idx != start {
+                idx = came_from[idx];
+                path.push(idx);
+            }
+            return Some(path);
+        }
+        if here_cost > cost[here] {
+            continue;
+        }
+        let (x, y) = (here as i32 % map.width, here as i32 / map.width);
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 1 || ny < 1 || nx > map.width - 2 || ny > map.height - 2 {
+                continue;
+            }
+            let next = map.xy_idx(nx, ny);
+            let step = if map.tiles[next] == TileType::Wall {
+                DIG_COST
+            } else {
+                1
+            };
+            if here_cost + step < cost[next] {
+                cost[next] = here_cost + step;
+                came_from[next] = here;
+                frontier.push(Reverse((cost[next], next)));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,43 @@
+use super::{apply_horizontal_tunnel, apply_vertical_tunnel, BuilderMap, MetaMapBuilder};
+use rltk::RandomNumberGenerator;
+
+/// Joins each room to the one placed before it with an L-shaped corridor between
+/// their centres, bending one way or the other at random.
+pub struct DoglegCorridors {}
+
+impl MetaMapBuilder for DoglegCorridors {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        let mut corridors = Vec::new();
+        for pair in rooms.windows(2) {
+            let (prev_x, prev_y) = pair[0].center();
+            let (new_x, new_y) = pair[1].center();
+            // Either along the first room's row then down, or down then along
+            let (row, column) = if rng.range(0, 2) == 1 {
+                (prev_y, new_x)
+            } else {
+                (new_y, prev_x)
+            };
+            let mut corridor = apply_horizontal_tunnel(&mut build_data.map, prev_x, new_x, row);
+            build_data.take_snapshot();
+            corridor.extend(apply_vertical_tunnel(
+                &mut build_data.map,
+                prev_y,
+                new_y,
+                column,
+            ));
+            build_data.take_snapshot();
+            corridors.push(corridor);
+        }
+        build_data.corridors = Some(corridors);
+    }
+}
+
+impl DoglegCorridors {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<DoglegCorridors> {
+        Box::new(DoglegCorridors {})
+    }
+}
@@ -0,0 +1,62 @@
+use super::{draw_corridor, BuilderMap, MetaMapBuilder};
+use rltk::{DistanceAlg, Point, RandomNumberGenerator};
+
+/// Grows a network out from the first room: again and again, whichever unjoined room
+/// is closest to a joined one gets a corridor to it. Every room ends up connected,
+/// each by the shortest link available at the time.
+pub struct NearestCorridors {}
+
+impl MetaMapBuilder for NearestCorridors {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        let centers: Vec<Point> = rooms
+            .iter()
+            .map(|room| {
+                let (x, y) = room.center();
+                Point::new(x, y)
+            })
+            .collect();
+
+        let mut connected = vec![false; rooms.len()];
+        let mut corridors = Vec::new();
+        if let Some(first) = connected.first_mut() {
+            *first = true;
+        }
+        while connected.iter().any(|joined| !joined) {
+            let (from, to, _distance) = (0..rooms.len())
+                .filter(|i| connected[*i])
+                .flat_map(|i| {
+                    (0..rooms.len())
+                        .filter(|j| !connected[*j])
+                        .map(move |j| (i, j))
+                })
+                .map(|(i, j)| {
+                    let distance = DistanceAlg::Pythagoras.distance2d(centers[i], centers[j]);
+                    (i, j, distance)
+                })
+                .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+                .unwrap();
+
+            let corridor = draw_corridor(
+                &mut build_data.map,
+                centers[from].x,
+                centers[from].y,
+                centers[to].x,
+                centers[to].y,
+            );
+            build_data.take_snapshot();
+            corridors.push(corridor);
+            connected[to] = true;
+        }
+        build_data.corridors = Some(corridors);
+    }
+}
+
+impl NearestCorridors {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<NearestCorridors> {
+        Box::new(NearestCorridors {})
+    }
+}
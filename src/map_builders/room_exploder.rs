@@ -0,0 +1,43 @@
+use super::{random_point_in_room, BuilderMap, MetaMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+/// How many steps each digger takes.
+const DIGGER_LIFETIME: i32 = 20;
+
+/// Roughens rooms into caverns: a handful of diggers stagger out from inside each
+/// room, knocking down whatever wall they walk into.
+pub struct RoomExploder {}
+
+impl MetaMapBuilder for RoomExploder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let Some(rooms) = build_data.rooms.clone() else {
+            return;
+        };
+        for room in rooms.iter() {
+            let map = &mut build_data.map;
+            let n_diggers = rng.roll_dice(1, 20) - 5;
+            for _ in 0..n_diggers {
+                let (mut x, mut y) = random_point_in_room(room, rng);
+                for _ in 0..DIGGER_LIFETIME {
+                    let idx = map.xy_idx(x, y);
+                    map.tiles[idx] = TileType::Floor;
+                    match rng.roll_dice(1, 4) {
+                        1 if x > 2 => x -= 1,
+                        2 if x < map.width - 2 => x += 1,
+                        3 if y > 2 => y -= 1,
+                        4 if y < map.height - 2 => y += 1,
+                        _ => {}
+                    }
+                }
+            }
+            build_data.take_snapshot();
+        }
+    }
+}
+
+impl RoomExploder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<RoomExploder> {
+        Box::new(RoomExploder {})
+    }
+}
@@ -0,0 +1,40 @@
+use super::{apply_room_to_map, BuilderMap, InitialMapBuilder, Rect};
+use rltk::RandomNumberGenerator;
+
+/// Rectangular rooms dropped wherever they fit. Joining them up is left to a
+/// corridor builder.
+pub struct SimpleMapBuilder {}
+
+impl InitialMapBuilder for SimpleMapBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut rooms: Vec<Rect> = Vec::new();
+        const MAX_ROOMS: i32 = 30;
+        const MIN_SIZE: i32 = 6;
+        const MAX_SIZE: i32 = 10;
+
+        for _ in 0..MAX_ROOMS {
+            let w = rng.range(MIN_SIZE, MAX_SIZE);
+            let h = rng.range(MIN_SIZE, MAX_SIZE);
+            let x = rng.roll_dice(1, build_data.map.width - w - 1) - 1;
+            let y = rng.roll_dice(1, build_data.map.height - h - 1) - 1;
+            let new_room = Rect::new(x, y, w, h);
+            let ok = rooms
+                .iter()
+                .all(|other_room| !new_room.intersect(other_room));
+
+            if ok {
+                apply_room_to_map(&mut build_data.map, &new_room);
+                build_data.take_snapshot();
+                rooms.push(new_room);
+            }
+        }
+        build_data.rooms = Some(rooms);
+    }
+}
+
+impl SimpleMapBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<SimpleMapBuilder> {
+        Box::new(SimpleMapBuilder {})
+    }
+}
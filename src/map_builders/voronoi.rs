@@ -0,0 +1,67 @@
+use super::{BuilderMap, InitialMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+const SEEDS: usize = 64;
+
+/// A hive of irregular cells: every tile belongs to its nearest seed point, and the
+/// borders between cells become walls.
+pub struct VoronoiCellBuilder {}
+
+impl InitialMapBuilder for VoronoiCellBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut seeds: Vec<rltk::Point> = Vec::new();
+        while seeds.len() < SEEDS {
+            let point = rltk::Point::new(
+                rng.roll_dice(1, build_data.map.width - 1),
+                rng.roll_dice(1, build_data.map.height - 1),
+            );
+            if !seeds.contains(&point) {
+                seeds.push(point);
+            }
+        }
+
+        let mut membership = vec![0usize; build_data.map.tiles.len()];
+        for (idx, member) in membership.iter_mut().enumerate() {
+            let x = idx as i32 % build_data.map.width;
+            let y = idx as i32 / build_data.map.width;
+            let here = rltk::Point::new(x, y);
+            *member = seeds
+                .iter()
+                .enumerate()
+                .map(|(seed, pos)| (seed, rltk::DistanceAlg::Pythagoras.distance2d(here, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .map(|(seed, _distance)| seed)
+                .unwrap();
+        }
+
+        // A tile bordering one other cell stays open, leaving doorways; a tile where
+        // more cells meet becomes wall
+        for y in 1..build_data.map.height - 1 {
+            for x in 1..build_data.map.width - 1 {
+                let idx = build_data.map.xy_idx(x, y);
+                let my_seed = membership[idx];
+                let neighbors = [
+                    build_data.map.xy_idx(x - 1, y),
+                    build_data.map.xy_idx(x + 1, y),
+                    build_data.map.xy_idx(x, y - 1),
+                    build_data.map.xy_idx(x, y + 1),
+                ]
+                .iter()
+                .filter(|i| membership[**i] != my_seed)
+                .count();
+
+                if neighbors < 2 {
+                    build_data.map.tiles[idx] = TileType::Floor;
+                }
+            }
+            build_data.take_snapshot();
+        }
+    }
+}
+
+impl VoronoiCellBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<VoronoiCellBuilder> {
+        Box::new(VoronoiCellBuilder {})
+    }
+}
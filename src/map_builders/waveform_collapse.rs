@@ -0,0 +1,184 @@
+use super::{BuilderMap, Map, MetaMapBuilder, TileType};
+use rltk::RandomNumberGenerator;
+
+/// Width and height, in tiles, of the pieces the sample is cut into.
+const CHUNK_SIZE: i32 = 8;
+/// Collapses that end in a contradiction are retried this many times before falling
+/// back to the sample itself.
+const MAX_ATTEMPTS: i32 = 10;
+
+type Chunk = Vec<TileType>;
+
+/// Wave function collapse: the map so far is cut into chunks and a new map is
+/// assembled from them, such that every pair of neighbours shares the same tiles
+/// along the edge where they meet. Whatever rooms and spawns the sample had are gone.
+pub struct WaveformCollapseBuilder {}
+
+impl MetaMapBuilder for WaveformCollapseBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let sample = build_data.map.clone();
+        let chunks = cut_into_chunks(&sample);
+        let compatible = compatibility(&chunks);
+        let chunks_wide = (sample.width / CHUNK_SIZE) as usize;
+        let chunks_high = (sample.height / CHUNK_SIZE) as usize;
+
+        let mut solution = None;
+        for _ in 0..MAX_ATTEMPTS {
+            solution = collapse(chunks.len(), &compatible, chunks_wide, chunks_high, rng);
+            if solution.is_some() {
+                break;
+            }
+        }
+        // Without a solution the sample is kept as it is
+        let Some(grid) = solution else {
+            return;
+        };
+
+        build_data.map.tiles = vec![TileType::Wall; sample.tiles.len()];
+        for (cell, chunk) in grid.iter().enumerate() {
+            let chunk_x = (cell % chunks_wide) as i32 * CHUNK_SIZE;
+            let chunk_y = (cell / chunks_wide) as i32 * CHUNK_SIZE;
+            for (i, tile) in chunks[*chunk].iter().enumerate() {
+                let x = chunk_x + i as i32 % CHUNK_SIZE;
+                let y = chunk_y + i as i32 / CHUNK_SIZE;
+                let idx = build_data.map.xy_idx(x, y);
+                build_data.map.tiles[idx] = *tile;
+            }
+            build_data.take_snapshot();
+        }
+        build_data.rooms = None;
+        build_data.corridors = None;
+        build_data.starting_position = None;
+        build_data.spawn_list.clear();
+    }
+}
+
+impl WaveformCollapseBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Box<WaveformCollapseBuilder> {
+        Box::new(WaveformCollapseBuilder {})
+    }
+}
+
+/// Every distinct `CHUNK_SIZE` square of `sample`, row by row.
+fn cut_into_chunks(sample: &Map) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = Vec::new();
+    for chunk_y in 0..sample.height / CHUNK_SIZE {
+        for chunk_x in 0..sample.width / CHUNK_SIZE {
+            let mut chunk = Vec::new();
+            for y in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    let idx = sample.xy_idx(chunk_x * CHUNK_SIZE + x, chunk_y * CHUNK_SIZE + y);
+                    chunk.push(sample.tiles[idx]);
+                }
+            }
+            if !chunks.contains(&chunk) {
+                chunks.push(chunk);
+            }
+        }
+    }
+    chunks
+}
+
+/// The tiles along one side of a chunk: 0 north, 1 south, 2 west, 3 east.
+fn edge(chunk: &Chunk, direction: usize) -> Vec<TileType> {
+    (0..CHUNK_SIZE)
+        .map(|i| {
+            let (x, y) = match direction {
+                0 => (i, 0),
+                1 => (i, CHUNK_SIZE - 1),
+                2 => (0, i),
+                _ => (CHUNK_SIZE - 1, i),
+            };
+            chunk[(y * CHUNK_SIZE + x) as usize]
+        })
+        .collect()
+}
+
+/// For each chunk and direction, which chunks may sit next to it on that side.
+fn compatibility(chunks: &[Chunk]) -> Vec<[Vec<bool>; 4]> {
+    const OPPOSITE: [usize; 4] = [1, 0, 3, 2];
+    chunks
+        .iter()
+        .map(|chunk| {
+            let mut allowed: [Vec<bool>; 4] = Default::default();
+            for (direction, allowed) in allowed.iter_mut().enumerate() {
+                let mine = edge(chunk, direction);
+                *allowed = chunks
+                    .iter()
+                    .map(|other| edge(other, OPPOSITE[direction]) == mine)
+                    .collect();
+            }
+            allowed
+        })
+        .collect()
+}
+
+/// Picks a chunk for every cell of the grid, most constrained cell first, narrowing
+/// down the neighbours after each pick. `None` when some cell runs out of options.
+fn collapse(
+    num_chunks: usize,
+    compatible: &[[Vec<bool>; 4]],
+    width: usize,
+    height: usize,
+    rng: &mut RandomNumberGenerator,
+) -> Option<Vec<usize>> {
+    let mut possible = vec![vec![true; num_chunks]; width * height];
+    let count = |options: &Vec<bool>| options.iter().filter(|o| **o).count();
+
+    loop {
+        let fewest = possible.iter().map(count).filter(|c| *c > 1).min();
+        let Some(fewest) = fewest else {
+            return Some(
+                possible
+                    .iter()
+                    .map(|options| options.iter().position(|o| *o).unwrap())
+                    .collect(),
+            );
+        };
+
+        let candidates: Vec<usize> = (0..possible.len())
+            .filter(|cell| count(&possible[*cell]) == fewest)
+            .collect();
+        let cell = candidates[(rng.roll_dice(1, candidates.len() as i32) - 1) as usize];
+        let options: Vec<usize> = (0..num_chunks).filter(|o| possible[cell][*o]).collect();
+        let choice = options[(rng.roll_dice(1, options.len() as i32) - 1) as usize];
+        for (option, allowed) in possible[cell].iter_mut().enumerate() {
+            *allowed = option == choice;
+        }
+
+        let mut to_visit = vec![cell];
+        while let Some(cell) = to_visit.pop() {
+            let (x, y) = (cell % width, cell / width);
+            let neighbors = [
+                (0, y > 0, cell.wrapping_sub(width)),
+                (1, y + 1 < height, cell + width),
+                (2, x > 0, cell.wrapping_sub(1)),
+                (3, x + 1 < width, cell + 1),
+            ];
+            for (direction, exists, neighbor) in neighbors {
+                if !exists {
+                    continue;
+                }
+                let mut changed = false;
+                for option in 0..num_chunks {
+                    if !possible[neighbor][option] {
+                        continue;
+                    }
+                    let supported = (0..num_chunks)
+                        .any(|mine| possible[cell][mine] && compatible[mine][direction][option]);
+                    if !supported {
+                        possible[neighbor][option] = false;
+                        changed = true;
+                    }
+                }
+                if count(&possible[neighbor]) == 0 {
+                    return None;
+                }
+                if changed {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{
+    dodge_bonus, gamelog::GameLog, hunger_power_bonus, melee_bonus, Attributes, CombatStats,
+    DefenseBonus, Equipped, HungerClock, MeleePowerBonus, Name, Player, Skills, SufferDamage,
+    WantsToMelee,
+};
+use rltk::RGB;
+use specs::prelude::*;
+
+pub struct MeleeCombatSystem {}
+
+impl<'a> System<'a> for MeleeCombatSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, WantsToMelee>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, MeleePowerBonus>,
+        ReadStorage<'a, DefenseBonus>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, HungerClock>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut log,
+            mut wants_melee,
+            names,
+            combat_stats,
+            mut inflict_damage,
+            players,
+            melee_power_bonuses,
+            defense_bonuses,
+            equipped,
+            hunger_clocks,
+            attributes,
+            skills,
+        ) = data;
+
+        for (entity, wants_melee, name, stats) in
+            (&entities, &wants_melee, &names, &combat_stats).join()
+        {
+            if stats.hp <= 0 {
+                continue;
+            }
+
+            let target_stats = combat_stats.get(wants_melee.target);
+            if let Some(target_stats) = target_stats {
+                if target_stats.hp > 0 {
+                    let target_name = names.get(wants_melee.target).unwrap();
+                    // Blows against the player are the ones worth noticing
+                    let color = if players.get(wants_melee.target).is_some() {
+                        RGB::named(rltk::ORANGE)
+                    } else {
+                        RGB::named(rltk::WHITE)
+                    };
+
+                    let power = stats.power
+                        + power_bonus(entity, &equipped, &melee_power_bonuses)
+                        + hunger_power_bonus(hunger_clocks.get(entity))
+                        + melee_bonus(attributes.get(entity), skills.get(entity));
+                    let defense = target_stats.defense
+                        + defense_bonus(wants_melee.target, &equipped, &defense_bonuses)
+                        + dodge_bonus(
+                            attributes.get(wants_melee.target),
+                            skills.get(wants_melee.target),
+                        );
+                    // Blows land for power less defense with no dice involved, so
+                    // attributes and skills count as flat bonuses rather than rolls
+                    let damage = i32::max(0, power - defense);
+                    if damage == 0 {
+                        log.push(
+                            format!("{} is unable to hurt {}.", &name.name, &target_name.name),
+                            color,
+                        );
+                    } else {
+                        log.push(
+                            format!(
+                                "{} hits {}, for {} hp.",
+                                &name.name, &target_name.name, damage
+                            ),
+                            color,
+                        );
+                        SufferDamage::new_damage(
+                            &mut inflict_damage,
+                            wants_melee.target,
+                            damage,
+                            players.get(entity).is_some(),
+                        );
+                    }
+                }
+            }
+        }
+
+        wants_melee.clear();
+    }
+}
+
+/// Total melee power granted by everything `owner` has equipped.
+pub fn power_bonus(
+    owner: Entity,
+    equipped: &ReadStorage<Equipped>,
+    bonuses: &ReadStorage<MeleePowerBonus>,
+) -> i32 {
+    (equipped, bonuses)
+        .join()
+        .filter(|(item, _bonus)| item.owner == owner)
+        .map(|(_item, bonus)| bonus.power)
+        .sum()
+}
+
+/// Total defense granted by everything `owner` has equipped.
+pub fn defense_bonus(
+    owner: Entity,
+    equipped: &ReadStorage<Equipped>,
+    bonuses: &ReadStorage<DefenseBonus>,
+) -> i32 {
+    (equipped, bonuses)
+        .join()
+        .filter(|(item, _bonus)| item.owner == owner)
+        .map(|(_item, bonus)| bonus.defense)
+        .sum()
+}
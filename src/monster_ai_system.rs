@@ -0,0 +1,87 @@
+use super::{Confusion, Map, Monster, Position, RunState, Viewshed, WantsToMelee};
+use rltk::{DistanceAlg, Point};
+use specs::prelude::*;
+
+pub struct MonsterAI {}
+
+impl<'a> System<'a> for MonsterAI {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        Entities<'a>,
+        WriteStorage<'a, Viewshed>,
+        ReadStorage<'a, Monster>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, WantsToMelee>,
+        WriteStorage<'a, Confusion>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            player_pos,
+            player_entity,
+            runstate,
+            entities,
+            mut viewshed,
+            monster,
+            mut position,
+            mut wants_to_melee,
+            mut confused,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        for (entity, viewshed, _monster, pos) in
+            (&entities, &mut viewshed, &monster, &mut position).join()
+        {
+            if let Some(confusion) = confused.get_mut(entity) {
+                confusion.turns -= 1;
+                if confusion.turns < 1 {
+                    confused.remove(entity);
+                }
+                continue;
+            }
+
+            if !viewshed.visible_tiles.contains(&*player_pos) {
+                continue;
+            }
+
+            let distance =
+                DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
+            if distance < 1.5
+                && !map.cuts_wall_corner(pos.x, pos.y, player_pos.x - pos.x, player_pos.y - pos.y)
+            {
+                wants_to_melee
+                    .insert(
+                        entity,
+                        WantsToMelee {
+                            target: *player_entity,
+                        },
+                    )
+                    .expect("Unable to insert attack");
+                continue;
+            }
+
+            let path = rltk::a_star_search(
+                map.xy_idx(pos.x, pos.y),
+                map.xy_idx(player_pos.x, player_pos.y),
+                &*map,
+            );
+            if path.success && path.steps.len() > 1 {
+                // Keep the index current so monsters moving later this turn don't stack up
+                let idx = map.xy_idx(pos.x, pos.y);
+                map.blocked[idx] = false;
+                pos.x = path.steps[1] as i32 % map.width;
+                pos.y = path.steps[1] as i32 / map.width;
+                let idx = map.xy_idx(pos.x, pos.y);
+                map.blocked[idx] = true;
+                viewshed.dirty = true;
+            }
+        }
+    }
+}
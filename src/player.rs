@@ -1,36 +1,130 @@
-use super::{Map, Player, Position, State, TileType, Viewshed};
-use rltk::{Rltk, VirtualKeyCode};
+use super::{
+    gamelog::GameLog, BlocksTile, BlocksVisibility, CombatStats, Command, Door, Item, Keymap, Map,
+    Player, Position, Renderable, Viewshed, WantsToMelee, WantsToPickupItem,
+};
+use rltk::{DistanceAlg, Point, Rltk, RGB};
 use specs::prelude::*;
-use std::cmp::{max, min};
 
-fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut positions = ecs.write_storage::<Position>();
     let mut players = ecs.write_storage::<Player>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
-    let map = ecs.fetch::<Map>();
+    let combat_stats = ecs.read_storage::<CombatStats>();
+    let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
+    let mut doors = ecs.write_storage::<Door>();
+    let mut blocks_movement = ecs.write_storage::<BlocksTile>();
+    let mut blocks_visibility = ecs.write_storage::<BlocksVisibility>();
+    let mut renderables = ecs.write_storage::<Renderable>();
+    let entities = ecs.entities();
+    let mut map = ecs.fetch_mut::<Map>();
 
-    for (_player, pos, viewshed) in (&mut players, &mut positions, &mut viewsheds).join() {
-        let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
-        if map.tiles[destination_idx] != TileType::Wall {
-            pos.x = min(crate::WIDTH - 1, max(0, pos.x + delta_x));
-            pos.y = min(crate::HEIGHT - 1, max(0, pos.y + delta_y));
+    for (entity, _player, pos, viewshed) in
+        (&entities, &mut players, &mut positions, &mut viewsheds).join()
+    {
+        let (destination_x, destination_y) = (pos.x + delta_x, pos.y + delta_y);
+        if !map.in_bounds(destination_x, destination_y)
+            || map.cuts_wall_corner(pos.x, pos.y, delta_x, delta_y)
+        {
+            continue;
+        }
+        let destination_idx = map.xy_idx(destination_x, destination_y);
+
+        // Bumping into anything that can fight back is an attack
+        for potential_target in map.tile_content[destination_idx].iter() {
+            if combat_stats.get(*potential_target).is_some() {
+                wants_to_melee
+                    .insert(
+                        entity,
+                        WantsToMelee {
+                            target: *potential_target,
+                        },
+                    )
+                    .expect("Add target failed");
+                return;
+            }
+            // Walking into a closed door opens it, which takes the move
+            if let Some(door) = doors.get_mut(*potential_target) {
+                if !door.open {
+                    door.open = true;
+                    blocks_movement.remove(*potential_target);
+                    blocks_visibility.remove(*potential_target);
+                    if let Some(render) = renderables.get_mut(*potential_target) {
+                        render.glyph = rltk::to_cp437('/');
+                    }
+                    map.blocked[destination_idx] = false;
+                    map.view_blocked.remove(&destination_idx);
+                    viewshed.dirty = true;
+                    return;
+                }
+            }
+        }
+
+        if !map.blocked[destination_idx] {
+            pos.x = destination_x;
+            pos.y = destination_y;
+
+            let mut player_pos = ecs.write_resource::<Point>();
+            player_pos.x = pos.x;
+            player_pos.y = pos.y;
 
             viewshed.dirty = true;
         }
     }
 }
 
-pub fn player_input(gs: &mut State, ctx: &mut Rltk) {
-    // Player movement
-    match ctx.key {
-        None => {} // Nothing happened
-        Some(key) => match key {
-            VirtualKeyCode::W => try_move_player(0, -1, &mut gs.ecs),
-            VirtualKeyCode::A => try_move_player(-1, 0, &mut gs.ecs),
-            VirtualKeyCode::S => try_move_player(0, 1, &mut gs.ecs),
-            VirtualKeyCode::D => try_move_player(1, 0, &mut gs.ecs),
-
-            _ => {}
-        },
+/// Queues picking up whatever item the player is standing on. Returns `false`, and
+/// says so in the log, if there is nothing there.
+pub fn get_item(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let items = ecs.read_storage::<Item>();
+    let positions = ecs.read_storage::<Position>();
+    let mut gamelog = ecs.fetch_mut::<GameLog>();
+
+    let target_item = (&entities, &items, &positions)
+        .join()
+        .find(|(_entity, _item, position)| position.x == player_pos.x && position.y == player_pos.y)
+        .map(|(entity, _item, _position)| entity);
+
+    match target_item {
+        None => {
+            gamelog.push("There is nothing here to pick up.", RGB::named(rltk::GRAY));
+            false
+        }
+        Some(item) => {
+            let mut pickup = ecs.write_storage::<WantsToPickupItem>();
+            pickup
+                .insert(
+                    *player_entity,
+                    WantsToPickupItem {
+                        collected_by: *player_entity,
+                        item,
+                    },
+                )
+                .expect("Unable to insert want to pickup");
+            true
+        }
     }
 }
+
+/// Tiles the player can see that are no more than `range` away: where a ranged item can be aimed.
+pub fn targetable_tiles(ecs: &World, range: i32) -> Vec<Point> {
+    let player_entity = ecs.fetch::<Entity>();
+    let player_pos = ecs.fetch::<Point>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+
+    match viewsheds.get(*player_entity) {
+        None => Vec::new(),
+        Some(visible) => visible
+            .visible_tiles
+            .iter()
+            .filter(|tile| DistanceAlg::Pythagoras.distance2d(*player_pos, **tile) <= range as f32)
+            .copied()
+            .collect(),
+    }
+}
+
+pub fn player_input(ctx: &mut Rltk, keymap: &Keymap) -> Option<Command> {
+    ctx.key.and_then(|key| keymap.command_for(key))
+}
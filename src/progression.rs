@@ -0,0 +1,70 @@
+use super::{Attributes, CombatStats, Experience, Mana, Skills};
+use rltk::RandomNumberGenerator;
+
+/// Experience needed to go from `level` to the next.
+pub fn xp_to_next_level(level: i32) -> i32 {
+    level * 1000
+}
+
+/// Experience for killing something of `level`.
+pub fn xp_for_kill(level: i32) -> i32 {
+    level * 100
+}
+
+/// Extra melee power from might and the melee skill.
+pub fn melee_bonus(attributes: Option<&Attributes>, skills: Option<&Skills>) -> i32 {
+    attributes.map_or(0, |attributes| attributes.might.bonus())
+        + skills.map_or(0, |skills| skills.melee)
+}
+
+/// Extra defense from quickness and the defense skill.
+pub fn dodge_bonus(attributes: Option<&Attributes>, skills: Option<&Skills>) -> i32 {
+    attributes.map_or(0, |attributes| attributes.quickness.bonus())
+        + skills.map_or(0, |skills| skills.defense)
+}
+
+/// Extra damage from magic items, from intelligence and the magic skill.
+pub fn magic_bonus(attributes: Option<&Attributes>, skills: Option<&Skills>) -> i32 {
+    attributes.map_or(0, |attributes| attributes.intelligence.bonus())
+        + skills.map_or(0, |skills| skills.magic)
+}
+
+/// Adds `xp` and takes as many levels as it pays for. Each level raises one
+/// attribute and one skill at random, then max HP (by fitness) and max mana (by
+/// intelligence), and restores both in full. Returns the levels gained.
+pub fn gain_xp(
+    rng: &mut RandomNumberGenerator,
+    xp: i32,
+    experience: &mut Experience,
+    attributes: &mut Attributes,
+    skills: &mut Skills,
+    stats: &mut CombatStats,
+    mut mana: Option<&mut Mana>,
+) -> i32 {
+    experience.xp += xp;
+    let mut levels = 0;
+    while experience.xp >= xp_to_next_level(experience.level) {
+        experience.xp -= xp_to_next_level(experience.level);
+        experience.level += 1;
+        levels += 1;
+
+        match rng.roll_dice(1, 4) {
+            1 => attributes.might.base += 1,
+            2 => attributes.fitness.base += 1,
+            3 => attributes.quickness.base += 1,
+            _ => attributes.intelligence.base += 1,
+        }
+        match rng.roll_dice(1, 3) {
+            1 => skills.melee += 1,
+            2 => skills.defense += 1,
+            _ => skills.magic += 1,
+        }
+        stats.max_hp += i32::max(1, 8 + attributes.fitness.bonus());
+        stats.hp = stats.max_hp;
+        if let Some(mana) = mana.as_deref_mut() {
+            mana.max_mana += i32::max(1, 4 + attributes.intelligence.bonus());
+            mana.mana = mana.max_mana;
+        }
+    }
+    levels
+}
@@ -0,0 +1,44 @@
+use crate::EquipmentSlot;
+use serde::Deserialize;
+
+/// How an entity is drawn. Colours are `#RRGGBB`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawRenderable {
+    pub glyph: char,
+    pub fg: String,
+    pub bg: String,
+    /// Lower orders are drawn on top.
+    pub order: i32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawItem {
+    pub name: String,
+    pub renderable: Option<RawRenderable>,
+    pub consumable: Option<RawConsumable>,
+    pub equippable: Option<RawEquippable>,
+}
+
+/// What happens when the item is used up. A `range` means it has to be aimed.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RawConsumable {
+    /// Eating it leaves the eater well fed.
+    #[serde(default)]
+    pub food: bool,
+    pub heal: Option<i32>,
+    pub range: Option<i32>,
+    pub damage: Option<i32>,
+    pub area_of_effect: Option<i32>,
+    pub confusion: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawEquippable {
+    pub slot: EquipmentSlot,
+    pub power_bonus: Option<i32>,
+    pub defense_bonus: Option<i32>,
+}
@@ -0,0 +1,22 @@
+use super::RawRenderable;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawMob {
+    pub name: String,
+    pub renderable: Option<RawRenderable>,
+    pub vision_range: i32,
+    /// Sets the experience for killing it; 1 if left out. Deeper finds count as higher.
+    pub level: Option<i32>,
+    pub stats: RawMobStats,
+}
+
+/// Stats on the first level; monsters found deeper are tougher.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawMobStats {
+    pub max_hp: i32,
+    pub defense: i32,
+    pub power: i32,
+}
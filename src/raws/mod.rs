@@ -0,0 +1,183 @@
+use super::RandomTable;
+use rltk::RGB;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+mod item_structs;
+pub use item_structs::*;
+mod mob_structs;
+pub use mob_structs::*;
+mod prop_structs;
+pub use prop_structs::*;
+mod spawn_table_structs;
+pub use spawn_table_structs::*;
+
+/// The monsters, items and props used when no raws file is given; also documents
+/// the file format.
+pub const DEFAULT_RAWS: &str = include_str!("../../raws/spawns.json");
+
+/// One entry of the raws, whichever list it came from.
+#[derive(Debug, Clone)]
+pub enum RawEntity {
+    Mob(RawMob),
+    Item(RawItem),
+    Prop(RawProp),
+}
+
+/// Everything that can be spawned, by name, and how often it turns up at each depth.
+/// Kept as a resource so the spawner can build entities from it.
+#[derive(Debug, Clone)]
+pub struct RawMaster {
+    entities: HashMap<String, RawEntity>,
+    spawn_table: Vec<SpawnTableEntry>,
+}
+
+impl RawMaster {
+    /// Parses a JSON object of `mobs`, `items`, `props` and `spawn_table` lists. Any
+    /// list may be left out. Errors name the entry at fault.
+    pub fn parse(text: &str) -> Result<RawMaster, String> {
+        let raws: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let sections = raws
+            .as_object()
+            .ok_or("expected an object of mobs, items, props and spawn_table")?;
+        if let Some(section) = sections
+            .keys()
+            .find(|key| !["mobs", "items", "props", "spawn_table"].contains(&key.as_str()))
+        {
+            return Err(format!("unknown section '{}'", section));
+        }
+
+        let mut entities = HashMap::new();
+        let mobs = entries::<RawMob>(&raws, "mobs")?;
+        let items = entries::<RawItem>(&raws, "items")?;
+        let props = entries::<RawProp>(&raws, "props")?;
+        let named = mobs
+            .into_iter()
+            .map(|(at, mob)| {
+                (
+                    at,
+                    mob.name.clone(),
+                    mob.renderable.clone(),
+                    RawEntity::Mob(mob),
+                )
+            })
+            .chain(items.into_iter().map(|(at, item)| {
+                (
+                    at,
+                    item.name.clone(),
+                    item.renderable.clone(),
+                    RawEntity::Item(item),
+                )
+            }))
+            .chain(props.into_iter().map(|(at, prop)| {
+                (
+                    at,
+                    prop.name.clone(),
+                    prop.renderable.clone(),
+                    RawEntity::Prop(prop),
+                )
+            }));
+        for (at, name, renderable, entity) in named {
+            if let Some(renderable) = renderable {
+                for colour in [&renderable.fg, &renderable.bg] {
+                    RGB::from_hex(colour)
+                        .map_err(|_| format!("{}: invalid colour '{}'", at, colour))?;
+                }
+            }
+            if let RawEntity::Item(item) = &entity {
+                // Using an item either equips it or uses it up, not both
+                if item.consumable.is_some() && item.equippable.is_some() {
+                    return Err(format!("{}: can't be both consumable and equippable", at));
+                }
+            }
+            if entities.insert(name.clone(), entity).is_some() {
+                return Err(format!("{}: '{}' is already defined", at, name));
+            }
+        }
+
+        let spawn_table = entries::<SpawnTableEntry>(&raws, "spawn_table")?;
+        for (at, entry) in spawn_table.iter() {
+            match entities.get(&entry.name) {
+                None => return Err(format!("{}: nothing is called '{}'", at, entry.name)),
+                // Props only appear where a builder puts them, never from the tables
+                Some(RawEntity::Prop(_)) => {
+                    return Err(format!("{}: props can't be spawned at random", at))
+                }
+                Some(_) => {}
+            }
+            if entry.weight < 1 {
+                return Err(format!("{}: weight must be at least 1", at));
+            }
+            if entry.min_depth > entry.max_depth {
+                return Err(format!("{}: min_depth is deeper than max_depth", at));
+            }
+        }
+
+        Ok(RawMaster {
+            entities,
+            spawn_table: spawn_table.into_iter().map(|(_at, entry)| entry).collect(),
+        })
+    }
+
+    pub fn load(path: &str) -> Result<RawMaster, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        RawMaster::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RawEntity> {
+        self.entities.get(name)
+    }
+
+    /// The monsters that can turn up at `depth`, weighted.
+    pub fn monster_table(&self, depth: i32) -> RandomTable {
+        self.table(depth, |entity| matches!(entity, RawEntity::Mob(_)))
+    }
+
+    /// The items that can turn up at `depth`, weighted.
+    pub fn item_table(&self, depth: i32) -> RandomTable {
+        self.table(depth, |entity| matches!(entity, RawEntity::Item(_)))
+    }
+
+    fn table(&self, depth: i32, wanted: impl Fn(&RawEntity) -> bool) -> RandomTable {
+        let mut table = RandomTable::new();
+        for entry in self.spawn_table.iter() {
+            if depth >= entry.min_depth
+                && depth <= entry.max_depth
+                && wanted(&self.entities[&entry.name])
+            {
+                table = table.add(&entry.name, entry.weight);
+            }
+        }
+        table
+    }
+}
+
+impl Default for RawMaster {
+    fn default() -> Self {
+        RawMaster::parse(DEFAULT_RAWS).expect("Built-in raws are invalid")
+    }
+}
+
+/// Reads each entry of the list called `section`, paired with where it is for error
+/// messages: `mobs[2] 'Orc'`, or just `mobs[2]` if it has no name.
+fn entries<T: DeserializeOwned>(raws: &Value, section: &str) -> Result<Vec<(String, T)>, String> {
+    let list = match raws.get(section) {
+        None => return Ok(Vec::new()),
+        Some(list) => list
+            .as_array()
+            .ok_or_else(|| format!("{}: expected a list", section))?,
+    };
+    list.iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let at = match entry.get("name").and_then(Value::as_str) {
+                Some(name) => format!("{}[{}] '{}'", section, i, name),
+                None => format!("{}[{}]", section, i),
+            };
+            T::deserialize(entry)
+                .map(|parsed| (at.clone(), parsed))
+                .map_err(|e| format!("{}: {}", at, e))
+        })
+        .collect()
+}
@@ -0,0 +1,17 @@
+use super::RawRenderable;
+use serde::Deserialize;
+
+/// Scenery: things that are neither monsters nor carried around.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawProp {
+    pub name: String,
+    pub renderable: Option<RawRenderable>,
+    #[serde(default)]
+    pub blocks_tile: bool,
+    #[serde(default)]
+    pub blocks_visibility: bool,
+    /// A closed door, opened by walking into it.
+    #[serde(default)]
+    pub door: bool,
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// How often `name` turns up, relative to everything else that can spawn between
+/// `min_depth` and `max_depth` (inclusive).
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnTableEntry {
+    pub name: String,
+    pub weight: i32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+}
@@ -0,0 +1,236 @@
+use super::{components::*, gamelog::GameLog, Map, MasterDungeonMap, RunStats, Seed};
+use rltk::{Point, RandomNumberGenerator};
+use serde::{Deserialize, Serialize};
+use specs::error::NoError;
+use specs::prelude::*;
+use specs::saveload::{
+    DeserializeComponents, MarkedBuilder, SerializeComponents, SimpleMarker, SimpleMarkerAllocator,
+};
+use std::path::Path;
+
+/// Bumped whenever the save format changes; older files are refused rather than misread.
+pub const SAVE_VERSION: u32 = 7;
+
+/// Where the front-end keeps the save unless told otherwise.
+pub const SAVE_FILE: &str = "savegame.json";
+
+/// Written ahead of the components: what the save is, and the resources that are not
+/// attached to any entity.
+#[derive(Serialize, Deserialize)]
+struct SaveHeader {
+    version: u32,
+    seed: u64,
+    /// Where the dice had got to, so levels generated after loading are the ones the
+    /// seed would have produced anyway.
+    rng: RandomNumberGenerator,
+    log: GameLog,
+    stats: RunStats,
+    dungeon: MasterDungeonMap,
+}
+
+macro_rules! serialize_individually {
+    ($ecs:expr, $ser:expr, $data:expr, $($type:ty),* $(,)?) => {
+        $(
+        SerializeComponents::<NoError, SimpleMarker<SerializeMe>>::serialize(
+            &($ecs.read_storage::<$type>(),),
+            &$data.0,
+            &$data.1,
+            &mut $ser,
+        )
+        .map_err(|e| e.to_string())?;
+        )*
+    };
+}
+
+macro_rules! deserialize_individually {
+    ($ecs:expr, $de:expr, $data:expr, $($type:ty),* $(,)?) => {
+        $(
+        DeserializeComponents::<NoError, _>::deserialize(
+            &mut (&mut $ecs.write_storage::<$type>(),),
+            &$data.0,
+            &mut $data.1,
+            &mut $data.2,
+            &mut $de,
+        )
+        .map_err(|e| format!("corrupt save: {}", e))?;
+        )*
+    };
+}
+
+/// Serializes every marked entity, the map and the log.
+pub fn save_game(ecs: &mut World) -> Result<String, String> {
+    // The map is a resource, so it travels as a component on a temporary entity
+    let map = (*ecs.fetch::<Map>()).clone();
+    let helper = ecs
+        .create_entity()
+        .with(SerializationHelper { map })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+
+    let result = write_world(ecs);
+
+    ecs.delete_entity(helper).expect("Crash on cleanup");
+    result
+}
+
+fn write_world(ecs: &World) -> Result<String, String> {
+    let header = SaveHeader {
+        version: SAVE_VERSION,
+        seed: ecs.fetch::<Seed>().0,
+        rng: (*ecs.fetch::<RandomNumberGenerator>()).clone(),
+        log: (*ecs.fetch::<GameLog>()).clone(),
+        stats: *ecs.fetch::<RunStats>(),
+        dungeon: (*ecs.fetch::<MasterDungeonMap>()).clone(),
+    };
+
+    let mut writer = Vec::new();
+    {
+        let mut serializer = serde_json::Serializer::new(&mut writer);
+        header
+            .serialize(&mut serializer)
+            .map_err(|e| e.to_string())?;
+
+        let data = (
+            ecs.entities(),
+            ecs.read_storage::<SimpleMarker<SerializeMe>>(),
+        );
+        serialize_individually!(
+            ecs,
+            serializer,
+            data,
+            Position,
+            OtherLevelPosition,
+            Renderable,
+            Player,
+            Viewshed,
+            Monster,
+            Name,
+            BlocksTile,
+            BlocksVisibility,
+            Door,
+            CombatStats,
+            HungerClock,
+            Attributes,
+            Skills,
+            Experience,
+            Mana,
+            Item,
+            Consumable,
+            ProvidesHealing,
+            ProvidesFood,
+            InflictsDamage,
+            Ranged,
+            AreaOfEffect,
+            Confusion,
+            InBackpack,
+            Equippable,
+            Equipped,
+            MeleePowerBonus,
+            DefenseBonus,
+            SerializationHelper,
+        );
+    }
+    String::from_utf8(writer).map_err(|e| e.to_string())
+}
+
+/// Fills an empty world (components registered, marker allocator inserted) from `text`,
+/// restoring the maps, log, run stats, seed, dice, player entity and player position.
+pub fn load_game(ecs: &mut World, text: &str) -> Result<(), String> {
+    let mut de = serde_json::Deserializer::from_str(text);
+    let header =
+        SaveHeader::deserialize(&mut de).map_err(|e| format!("corrupt save header: {}", e))?;
+    if header.version != SAVE_VERSION {
+        return Err(format!(
+            "save version {} is not supported (expected {})",
+            header.version, SAVE_VERSION
+        ));
+    }
+
+    {
+        let mut data = (
+            &mut ecs.entities(),
+            &mut ecs.write_storage::<SimpleMarker<SerializeMe>>(),
+            &mut ecs.write_resource::<SimpleMarkerAllocator<SerializeMe>>(),
+        );
+        deserialize_individually!(
+            ecs,
+            de,
+            data,
+            Position,
+            OtherLevelPosition,
+            Renderable,
+            Player,
+            Viewshed,
+            Monster,
+            Name,
+            BlocksTile,
+            BlocksVisibility,
+            Door,
+            CombatStats,
+            HungerClock,
+            Attributes,
+            Skills,
+            Experience,
+            Mana,
+            Item,
+            Consumable,
+            ProvidesHealing,
+            ProvidesFood,
+            InflictsDamage,
+            Ranged,
+            AreaOfEffect,
+            Confusion,
+            InBackpack,
+            Equippable,
+            Equipped,
+            MeleePowerBonus,
+            DefenseBonus,
+            SerializationHelper,
+        );
+    }
+
+    let (helper, mut map) = {
+        let entities = ecs.entities();
+        let helpers = ecs.read_storage::<SerializationHelper>();
+        (&entities, &helpers)
+            .join()
+            .map(|(entity, helper)| (entity, helper.map.clone()))
+            .next()
+            .ok_or("corrupt save: no map")?
+    };
+    ecs.delete_entity(helper).expect("Crash on cleanup");
+    map.tile_content = vec![Vec::new(); map.tiles.len()];
+    ecs.insert(map);
+
+    let (player_entity, player_pos) = {
+        let entities = ecs.entities();
+        let players = ecs.read_storage::<Player>();
+        let positions = ecs.read_storage::<Position>();
+        (&entities, &players, &positions)
+            .join()
+            .map(|(entity, _player, pos)| (entity, Point::new(pos.x, pos.y)))
+            .next()
+            .ok_or("corrupt save: no player")?
+    };
+    ecs.insert(player_entity);
+    ecs.insert(player_pos);
+    ecs.insert(Seed(header.seed));
+    ecs.insert(header.rng);
+    ecs.insert(header.log);
+    ecs.insert(header.stats);
+    ecs.insert(header.dungeon);
+
+    Ok(())
+}
+
+pub fn does_save_exist(path: &Path) -> bool {
+    path.exists()
+}
+
+/// Permadeath: once the player dies their save goes with them.
+pub fn delete_save(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
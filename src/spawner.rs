@@ -0,0 +1,364 @@
+use super::raws::{RawEntity, RawItem, RawMaster, RawMob, RawProp, RawRenderable};
+use super::{
+    AreaOfEffect, Attribute, Attributes, BlocksTile, BlocksVisibility, CombatStats, Confusion,
+    Consumable, DefenseBonus, Door, Equippable, Experience, HungerClock, HungerState,
+    InflictsDamage, Item, Mana, Map, MeleePowerBonus, Monster, Name, Player, Position,
+    ProvidesFood, ProvidesHealing, RandomTable, Ranged, Rect, Renderable, SerializeMe, Skills,
+    TileType, Viewshed, WELL_FED_TURNS,
+};
+use rltk::{RandomNumberGenerator, RGB};
+use specs::prelude::*;
+use specs::saveload::{MarkedBuilder, SimpleMarker};
+
+pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: player_x,
+            y: player_y,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('@'),
+            fg: RGB::named(rltk::YELLOW),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 0,
+        })
+        .with(Player {})
+        .with(Viewshed {
+            visible_tiles: Vec::new(),
+            range: 8,
+            dirty: true,
+        })
+        .with(Name {
+            name: "Player".to_string(),
+        })
+        .with(CombatStats {
+            max_hp: 30,
+            hp: 30,
+            defense: 2,
+            power: 5,
+        })
+        .with(HungerClock {
+            state: HungerState::WellFed,
+            duration: WELL_FED_TURNS,
+        })
+        .with(Attributes {
+            might: Attribute { base: 11 },
+            fitness: Attribute { base: 11 },
+            quickness: Attribute { base: 11 },
+            intelligence: Attribute { base: 11 },
+        })
+        .with(Skills {
+            melee: 0,
+            defense: 0,
+            magic: 0,
+        })
+        .with(Experience { level: 1, xp: 0 })
+        .with(Mana {
+            max_mana: 4,
+            mana: 4,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+const MAX_ITEMS: i32 = 2;
+
+/// Fills `rooms`: a random monster in the middle of each but the first, which is
+/// the player's, and up to `MAX_ITEMS` items scattered around each. Deeper levels
+/// add up to `depth - 1` more monsters per room. Nothing is put on wall, or on the
+/// tiles in `taken`.
+pub fn spawn_rooms(ecs: &mut World, rooms: &[Rect], taken: &[usize]) {
+    for (i, room) in rooms.iter().enumerate() {
+        let mut used = unusable_room_points(ecs, room, taken);
+        let center = room.center();
+        let center_free = !used.contains(&center);
+        used.push(center);
+        if i > 0 {
+            if center_free {
+                random_monster(ecs, center.0, center.1);
+            }
+
+            let extra_monsters = roll_extra_monsters(ecs);
+            for (x, y) in random_room_points(ecs, room, extra_monsters, &mut used) {
+                random_monster(ecs, x, y);
+            }
+        }
+
+        let num_items = roll_items(ecs);
+        for (x, y) in random_room_points(ecs, room, num_items, &mut used) {
+            random_item(ecs, x, y);
+        }
+    }
+}
+
+/// Fills an irregular area of floor (tile indices) the way `spawn_rooms` fills a
+/// room, for maps that have no rooms.
+pub fn spawn_region(ecs: &mut World, area: &[usize], with_monsters: bool) {
+    let num_monsters = if with_monsters {
+        1 + roll_extra_monsters(ecs)
+    } else {
+        0
+    };
+    let num_items = roll_items(ecs);
+
+    let mut spawn_points: Vec<(i32, i32)> = Vec::new();
+    {
+        let map = ecs.fetch::<Map>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let mut areas: Vec<usize> = area.to_vec();
+        for _ in 0..usize::min(areas.len(), (num_monsters + num_items) as usize) {
+            let array_index = (rng.roll_dice(1, areas.len() as i32) - 1) as usize;
+            let idx = areas.remove(array_index) as i32;
+            spawn_points.push((idx % map.width, idx / map.width));
+        }
+    }
+
+    for (i, (x, y)) in spawn_points.into_iter().enumerate() {
+        if (i as i32) < num_monsters {
+            random_monster(ecs, x, y);
+        } else {
+            random_item(ecs, x, y);
+        }
+    }
+}
+
+/// Creates the entity called `name` at tile `idx`, for builders that place things
+/// by name. Returns `None` for a name the raws don't know.
+pub fn spawn_named(ecs: &mut World, idx: usize, name: &str) -> Option<Entity> {
+    let (x, y) = {
+        let map = ecs.fetch::<Map>();
+        (idx as i32 % map.width, idx as i32 / map.width)
+    };
+    spawn_at(ecs, name, x, y)
+}
+
+fn roll_extra_monsters(ecs: &mut World) -> i32 {
+    let depth = ecs.fetch::<Map>().depth;
+    ecs.write_resource::<RandomNumberGenerator>()
+        .roll_dice(1, depth)
+        - 1
+}
+
+fn roll_items(ecs: &mut World) -> i32 {
+    ecs.write_resource::<RandomNumberGenerator>()
+        .roll_dice(1, MAX_ITEMS + 1)
+        - 1
+}
+
+/// The points inside `room` that are not floor, or are already `taken`.
+fn unusable_room_points(ecs: &World, room: &Rect, taken: &[usize]) -> Vec<(i32, i32)> {
+    let map = ecs.fetch::<Map>();
+    let mut points = Vec::new();
+    for y in room.y1 + 1..=room.y2 {
+        for x in room.x1 + 1..=room.x2 {
+            let idx = map.xy_idx(x, y);
+            if map.tiles[idx] != TileType::Floor || taken.contains(&idx) {
+                points.push((x, y));
+            }
+        }
+    }
+    points
+}
+
+/// Up to `count` points inside `room` that are not already `taken`; each one picked
+/// is added to `taken`.
+fn random_room_points(
+    ecs: &mut World,
+    room: &Rect,
+    count: i32,
+    taken: &mut Vec<(i32, i32)>,
+) -> Vec<(i32, i32)> {
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut points = Vec::new();
+    for _ in 0..count {
+        // Room interiors run from x1 + 1 to x2
+        let x = room.x1 + rng.roll_dice(1, room.x2 - room.x1);
+        let y = room.y1 + rng.roll_dice(1, room.y2 - room.y1);
+        if !taken.contains(&(x, y)) {
+            taken.push((x, y));
+            points.push((x, y));
+        }
+    }
+    points
+}
+
+/// A monster from the spawn table for the current depth, or `None` if nothing can
+/// turn up this deep.
+pub fn random_monster(ecs: &mut World, x: i32, y: i32) -> Option<Entity> {
+    let table = {
+        let depth = ecs.fetch::<Map>().depth;
+        ecs.fetch::<RawMaster>().monster_table(depth)
+    };
+    roll_and_spawn(ecs, &table, x, y)
+}
+
+/// An item from the spawn table for the current depth, or `None` if nothing can turn
+/// up this deep.
+pub fn random_item(ecs: &mut World, x: i32, y: i32) -> Option<Entity> {
+    let table = {
+        let depth = ecs.fetch::<Map>().depth;
+        ecs.fetch::<RawMaster>().item_table(depth)
+    };
+    roll_and_spawn(ecs, &table, x, y)
+}
+
+fn roll_and_spawn(ecs: &mut World, table: &RandomTable, x: i32, y: i32) -> Option<Entity> {
+    let name = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        table.roll(&mut rng)?.to_string()
+    };
+    spawn_at(ecs, &name, x, y)
+}
+
+/// Builds the entity the raws call `name` at `(x, y)`. Returns `None` for a name
+/// they don't know.
+pub fn spawn_at(ecs: &mut World, name: &str, x: i32, y: i32) -> Option<Entity> {
+    let raw = ecs.fetch::<RawMaster>().get(name)?.clone();
+    let entity = match raw {
+        RawEntity::Mob(mob) => spawn_mob(ecs, &mob, x, y),
+        RawEntity::Item(item) => spawn_item(ecs, &item, x, y),
+        RawEntity::Prop(prop) => spawn_prop(ecs, &prop, x, y),
+    };
+    Some(entity)
+}
+
+fn with_renderable<'a>(
+    builder: EntityBuilder<'a>,
+    renderable: &Option<RawRenderable>,
+) -> EntityBuilder<'a> {
+    match renderable {
+        None => builder,
+        Some(renderable) => builder.with(Renderable {
+            glyph: rltk::to_cp437(renderable.glyph),
+            fg: RGB::from_hex(&renderable.fg).expect("Raws colours are checked when parsed"),
+            bg: RGB::from_hex(&renderable.bg).expect("Raws colours are checked when parsed"),
+            render_order: renderable.order,
+        }),
+    }
+}
+
+fn spawn_mob(ecs: &mut World, mob: &RawMob, x: i32, y: i32) -> Entity {
+    // Monsters toughen up the deeper they are found
+    let depth = ecs.fetch::<Map>().depth;
+    let max_hp = mob.stats.max_hp + 2 * (depth - 1);
+    let power = mob.stats.power + (depth - 1) / 2;
+
+    let builder = ecs.create_entity().with(Position { x, y });
+    with_renderable(builder, &mob.renderable)
+        .with(Viewshed {
+            visible_tiles: Vec::new(),
+            range: mob.vision_range,
+            dirty: true,
+        })
+        .with(Monster {})
+        .with(BlocksTile {})
+        .with(Name {
+            name: mob.name.clone(),
+        })
+        .with(CombatStats {
+            max_hp,
+            hp: max_hp,
+            defense: mob.stats.defense,
+            power,
+        })
+        .with(Experience {
+            level: mob.level.unwrap_or(1) + depth - 1,
+            xp: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+fn spawn_item(ecs: &mut World, item: &RawItem, x: i32, y: i32) -> Entity {
+    let builder = ecs.create_entity().with(Position { x, y });
+    let mut builder = with_renderable(builder, &item.renderable)
+        .with(Name {
+            name: item.name.clone(),
+        })
+        .with(Item {});
+    if let Some(consumable) = &item.consumable {
+        builder = builder.with(Consumable {});
+        if consumable.food {
+            builder = builder.with(ProvidesFood {});
+        }
+        if let Some(heal_amount) = consumable.heal {
+            builder = builder.with(ProvidesHealing { heal_amount });
+        }
+        if let Some(range) = consumable.range {
+            builder = builder.with(Ranged { range });
+        }
+        if let Some(damage) = consumable.damage {
+            builder = builder.with(InflictsDamage { damage });
+        }
+        if let Some(radius) = consumable.area_of_effect {
+            builder = builder.with(AreaOfEffect { radius });
+        }
+        if let Some(turns) = consumable.confusion {
+            builder = builder.with(Confusion { turns });
+        }
+    }
+    if let Some(equippable) = &item.equippable {
+        builder = builder.with(Equippable {
+            slot: equippable.slot,
+        });
+        if let Some(power) = equippable.power_bonus {
+            builder = builder.with(MeleePowerBonus { power });
+        }
+        if let Some(defense) = equippable.defense_bonus {
+            builder = builder.with(DefenseBonus { defense });
+        }
+    }
+    builder.marked::<SimpleMarker<SerializeMe>>().build()
+}
+
+fn spawn_prop(ecs: &mut World, prop: &RawProp, x: i32, y: i32) -> Entity {
+    let builder = ecs.create_entity().with(Position { x, y });
+    let mut builder = with_renderable(builder, &prop.renderable).with(Name {
+        name: prop.name.clone(),
+    });
+    if prop.blocks_tile {
+        builder = builder.with(BlocksTile {});
+    }
+    if prop.blocks_visibility {
+        builder = builder.with(BlocksVisibility {});
+    }
+    if prop.door {
+        builder = builder.with(Door { open: false });
+    }
+    builder.marked::<SimpleMarker<SerializeMe>>().build()
+}
+
+/// Spawns one of the standard raws by name, for code and tests that want that
+/// thing in particular.
+fn spawn_standard(ecs: &mut World, name: &str, x: i32, y: i32) -> Entity {
+    spawn_at(ecs, name, x, y).unwrap_or_else(|| panic!("The raws have no '{}'", name))
+}
+
+pub fn health_potion(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Health Potion", x, y)
+}
+
+pub fn magic_missile_scroll(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Magic Missile Scroll", x, y)
+}
+
+pub fn fireball_scroll(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Fireball Scroll", x, y)
+}
+
+pub fn confusion_scroll(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Confusion Scroll", x, y)
+}
+
+pub fn dagger(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Dagger", x, y)
+}
+
+pub fn shield(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Shield", x, y)
+}
+
+/// A closed door: it blocks movement and sight until the player walks into it.
+pub fn door(ecs: &mut World, x: i32, y: i32) -> Entity {
+    spawn_standard(ecs, "Door", x, y)
+}
@@ -22,14 +22,14 @@ impl<'a> System<'a> for VisibilitySystem {
                 viewshed.visible_tiles.clear();
                 viewshed.visible_tiles =
                     field_of_view(Point::new(pos.x, pos.y), viewshed.range, &*map);
-                viewshed
-                    .visible_tiles
-                    .retain(|p| p.x >= 0 && p.x < crate::WIDTH && p.y >= 0 && p.y < crate::HEIGHT);
+                viewshed.visible_tiles.retain(|p| map.in_bounds(p.x, p.y));
 
                 // If this is the player, reveal what they can see
                 let p: Option<&Player> = player.get(ent);
                 if let Some(_p) = p {
-                    for t in map.visible_tiles.iter_mut() { *t = false };
+                    for t in map.visible_tiles.iter_mut() {
+                        *t = false
+                    }
                     for vis in viewshed.visible_tiles.iter() {
                         let idx = map.xy_idx(vis.x, vis.y);
                         map.revealed_tiles[idx] = true;
@@ -0,0 +1,33 @@
+use rltk::Point;
+use rust_roguelike::camera::{player_position, Viewport};
+use rust_roguelike::*;
+
+#[test]
+fn viewport_is_centered_on_the_target() {
+    let viewport = Viewport::centered_on(Point::new(100, 60), 80, 50);
+    assert_eq!(viewport.to_screen(100, 60), Some((40, 25)));
+    assert_eq!(viewport.to_map(40, 25), (100, 60));
+}
+
+#[test]
+fn positions_outside_the_viewport_are_not_drawn() {
+    let viewport = Viewport::centered_on(Point::new(10, 10), 80, 50);
+    assert_eq!(viewport.min_x, -30);
+    assert_eq!(viewport.to_screen(-30, -15), Some((0, 0)));
+    assert_eq!(viewport.to_screen(50, 10), None);
+    assert_eq!(viewport.to_screen(10, 35), None);
+}
+
+#[test]
+fn the_game_map_is_larger_than_the_console() {
+    let game = Game::new(5);
+    let map = game.ecs.fetch::<Map>();
+    assert!(map.width > WIDTH && map.height > HEIGHT);
+
+    let viewport = Viewport::centered_on(player_position(&game.ecs), WIDTH, HEIGHT);
+    let player = player_position(&game.ecs);
+    assert_eq!(
+        viewport.to_screen(player.x, player.y),
+        Some((WIDTH / 2, HEIGHT / 2))
+    );
+}
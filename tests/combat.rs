@@ -0,0 +1,139 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn bumping_a_monster_attacks_it() {
+    let mut game = Game::new(31);
+    let monster = monster_next_to_player(&mut game);
+    let player_pos = *game.ecs.fetch::<Point>();
+
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    // Player power 5 against monster defense 1
+    assert_eq!(hp(&game, monster), 16 - 4);
+    assert_eq!(*game.ecs.fetch::<Point>(), player_pos);
+}
+
+#[test]
+fn adjacent_monsters_attack_the_player() {
+    let mut game = Game::new(31);
+    let player = *game.ecs.fetch::<Entity>();
+    monster_next_to_player(&mut game);
+    let before = hp(&game, player);
+
+    game.submit(Command::Wait);
+    // Monster power 4 against player defense 2
+    assert_eq!(hp(&game, player), before - 2);
+}
+
+#[test]
+fn monsters_that_walk_up_can_be_hit_straight_away() {
+    let mut game = Game::new(31);
+    let player_pos = *game.ecs.fetch::<Point>();
+    let monster = spawner::random_monster(&mut game.ecs, player_pos.x + 2, player_pos.y).unwrap();
+    game.submit(Command::Wait);
+    {
+        let positions = game.ecs.read_storage::<Position>();
+        let pos = positions.get(monster).unwrap();
+        assert_eq!((pos.x, pos.y), (player_pos.x + 1, player_pos.y));
+    }
+
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    assert_eq!(hp(&game, monster), 16 - 4);
+    assert_eq!(*game.ecs.fetch::<Point>(), player_pos);
+}
+
+#[test]
+fn dead_monsters_are_deleted() {
+    let mut game = Game::new(31);
+    let monster = monster_next_to_player(&mut game);
+
+    for _ in 0..4 {
+        game.submit(Command::Move {
+            delta_x: 1,
+            delta_y: 0,
+        });
+    }
+    assert!(!game.ecs.is_alive(monster));
+    assert_eq!(game.run_state(), RunState::AwaitingInput);
+}
+
+#[test]
+fn the_game_ends_when_the_player_dies() {
+    let mut game = Game::new(31);
+    let player = *game.ecs.fetch::<Entity>();
+    monster_next_to_player(&mut game);
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 1;
+
+    assert_eq!(game.submit(Command::Wait), RunState::GameOver);
+    assert!(game.ecs.is_alive(player));
+    assert_eq!(game.tick(Some(Command::Wait)), RunState::GameOver);
+}
+
+fn log_texts(game: &Game) -> Vec<String> {
+    game.ecs
+        .fetch::<gamelog::GameLog>()
+        .entries
+        .iter()
+        .map(|entry| entry.text.clone())
+        .collect()
+}
+
+#[test]
+fn attacks_and_deaths_are_logged() {
+    let mut game = Game::new(31);
+    let monster = monster_next_to_player(&mut game);
+    let name = game
+        .ecs
+        .read_storage::<Name>()
+        .get(monster)
+        .unwrap()
+        .name
+        .clone();
+
+    for _ in 0..4 {
+        game.submit(Command::Move {
+            delta_x: 1,
+            delta_y: 0,
+        });
+    }
+    let log = log_texts(&game);
+    assert!(log.contains(&format!("Player hits {}, for 4 hp.", name)));
+    assert!(log.contains(&format!("{} hits Player, for 2 hp.", name)));
+    assert_eq!(log.last().unwrap(), &format!("{} is dead.", name));
+}
+
+#[test]
+fn death_is_announced_once() {
+    let mut game = Game::new(31);
+    let player = *game.ecs.fetch::<Entity>();
+    monster_next_to_player(&mut game);
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 1;
+
+    game.submit(Command::Wait);
+    for _ in 0..10 {
+        game.tick(None);
+    }
+    let deaths = log_texts(&game)
+        .iter()
+        .filter(|text| *text == "You are dead.")
+        .count();
+    assert_eq!(deaths, 1);
+}
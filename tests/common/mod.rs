@@ -0,0 +1,30 @@
+//! Fixtures shared by the integration tests. Each test file uses only some of them.
+#![allow(dead_code)]
+
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+pub fn hp(game: &Game, entity: Entity) -> i32 {
+    game.ecs
+        .read_storage::<CombatStats>()
+        .get(entity)
+        .unwrap()
+        .hp
+}
+
+/// A random monster right beside the player, who waits a turn so it is indexed.
+pub fn monster_next_to_player(game: &mut Game) -> Entity {
+    let player_pos = *game.ecs.fetch::<Point>();
+    let monster = spawner::random_monster(&mut game.ecs, player_pos.x + 1, player_pos.y).unwrap();
+    game.submit(Command::Wait);
+    monster
+}
+
+/// Spawns an item under the player and picks it up.
+pub fn carry(game: &mut Game, spawn: fn(&mut World, i32, i32) -> Entity) -> Entity {
+    let player_pos = *game.ecs.fetch::<Point>();
+    let item = spawn(&mut game.ecs, player_pos.x, player_pos.y);
+    game.submit(Command::PickUp);
+    item
+}
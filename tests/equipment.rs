@@ -0,0 +1,140 @@
+use rust_roguelike::*;
+use specs::prelude::*;
+
+mod common;
+use common::*;
+
+fn equip(game: &mut Game, item: Entity) {
+    game.submit(Command::UseItem { item, target: None });
+}
+
+#[test]
+fn equipping_moves_the_item_out_of_the_backpack() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    let dagger = carry(&mut game, spawner::dagger);
+    equip(&mut game, dagger);
+
+    let equipped = game.ecs.read_storage::<Equipped>();
+    assert_eq!(equipped.get(dagger).unwrap().owner, player);
+    assert_eq!(equipped.get(dagger).unwrap().slot, EquipmentSlot::Melee);
+    assert!(game.ecs.read_storage::<InBackpack>().get(dagger).is_none());
+    assert!(game.ecs.is_alive(dagger));
+}
+
+#[test]
+fn equipping_into_an_occupied_slot_swaps_items() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    let first = carry(&mut game, spawner::dagger);
+    let second = carry(&mut game, spawner::dagger);
+    equip(&mut game, first);
+    equip(&mut game, second);
+
+    assert!(game.ecs.read_storage::<Equipped>().get(first).is_none());
+    assert_eq!(
+        game.ecs
+            .read_storage::<InBackpack>()
+            .get(first)
+            .unwrap()
+            .owner,
+        player
+    );
+    assert!(game.ecs.read_storage::<Equipped>().get(second).is_some());
+}
+
+#[test]
+fn other_slots_are_left_alone() {
+    let mut game = Game::new(61);
+    let dagger = carry(&mut game, spawner::dagger);
+    let shield = carry(&mut game, spawner::shield);
+    equip(&mut game, dagger);
+    equip(&mut game, shield);
+
+    let equipped = game.ecs.read_storage::<Equipped>();
+    assert!(equipped.get(dagger).is_some());
+    assert!(equipped.get(shield).is_some());
+}
+
+#[test]
+fn a_dagger_hits_harder() {
+    let mut game = Game::new(61);
+    let dagger = carry(&mut game, spawner::dagger);
+    equip(&mut game, dagger);
+    let monster = monster_next_to_player(&mut game);
+
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    // Power 5 + 2 against defense 1
+    assert_eq!(hp(&game, monster), 16 - 6);
+}
+
+#[test]
+fn a_shield_blocks_blows() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    let shield = carry(&mut game, spawner::shield);
+    equip(&mut game, shield);
+    monster_next_to_player(&mut game);
+
+    let before = hp(&game, player);
+    game.submit(Command::Wait);
+    // Power 4 against defense 2 + 1
+    assert_eq!(hp(&game, player), before - 1);
+}
+
+#[test]
+fn removed_items_go_back_in_the_backpack() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    let shield = carry(&mut game, spawner::shield);
+    equip(&mut game, shield);
+
+    assert_eq!(
+        game.tick(Some(Command::ShowRemoveItem)),
+        RunState::ShowRemoveItem
+    );
+    game.submit(Command::RemoveItem { item: shield });
+    assert!(game.ecs.read_storage::<Equipped>().get(shield).is_none());
+    assert_eq!(
+        game.ecs
+            .read_storage::<InBackpack>()
+            .get(shield)
+            .unwrap()
+            .owner,
+        player
+    );
+}
+
+#[test]
+fn aiming_something_equippable_does_not_equip_it() {
+    let mut game = Game::new(31);
+    let player = *game.ecs.fetch::<Entity>();
+    let dagger = carry(&mut game, spawner::dagger);
+    game.ecs
+        .write_storage::<Ranged>()
+        .insert(dagger, Ranged { range: 6 })
+        .unwrap();
+    game.ecs
+        .write_storage::<InflictsDamage>()
+        .insert(dagger, InflictsDamage { damage: 8 })
+        .unwrap();
+    let player_pos = *game.ecs.fetch::<rltk::Point>();
+
+    // Nothing stands on the target tile
+    game.submit(Command::UseItem {
+        item: dagger,
+        target: Some(rltk::Point::new(player_pos.x + 1, player_pos.y)),
+    });
+    assert!(game.ecs.read_storage::<Equipped>().get(dagger).is_none());
+    assert_eq!(
+        game.ecs
+            .read_storage::<InBackpack>()
+            .get(dagger)
+            .unwrap()
+            .owner,
+        player
+    );
+}
@@ -0,0 +1,67 @@
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn player_position(game: &Game) -> (i32, i32) {
+    let positions = game.ecs.read_storage::<Position>();
+    let players = game.ecs.read_storage::<Player>();
+    let (pos, _player) = (&positions, &players).join().next().unwrap();
+    (pos.x, pos.y)
+}
+
+#[test]
+fn player_starts_in_the_first_room() {
+    let game = Game::new(1);
+    let map = game.ecs.fetch::<Map>();
+    assert!(!map.rooms.is_empty());
+    assert_eq!(player_position(&game), map.rooms[0].center());
+}
+
+#[test]
+fn visibility_is_computed_before_the_first_turn() {
+    let game = Game::new(1);
+    let (x, y) = player_position(&game);
+    let map = game.ecs.fetch::<Map>();
+    let idx = map.xy_idx(x, y);
+    assert!(map.visible_tiles[idx]);
+    assert!(map.revealed_tiles[idx]);
+}
+
+#[test]
+fn waiting_does_not_move_the_player() {
+    let mut game = Game::new(1);
+    let start = player_position(&game);
+    game.submit(Command::Wait);
+    assert_eq!(player_position(&game), start);
+}
+
+#[test]
+fn walls_stop_movement() {
+    let mut game = Game::new(1);
+    for _ in 0..WIDTH {
+        game.submit(Command::Move {
+            delta_x: -1,
+            delta_y: 0,
+        });
+    }
+    let (x, y) = player_position(&game);
+    let map = game.ecs.fetch::<Map>();
+    assert_eq!(map.tiles[map.xy_idx(x - 1, y)], TileType::Wall);
+}
+
+#[test]
+fn moving_marks_the_viewshed_dirty_and_refreshes_it() {
+    let mut game = Game::new(1);
+    let (x, y) = player_position(&game);
+    try_move_player(1, 0, &mut game.ecs);
+    {
+        let viewsheds = game.ecs.read_storage::<Viewshed>();
+        let players = game.ecs.read_storage::<Player>();
+        let (viewshed, _player) = (&viewsheds, &players).join().next().unwrap();
+        assert_eq!(viewshed.dirty, player_position(&game) != (x, y));
+    }
+    game.submit(Command::Wait);
+    let viewsheds = game.ecs.read_storage::<Viewshed>();
+    let players = game.ecs.read_storage::<Player>();
+    let (viewshed, _player) = (&viewsheds, &players).join().next().unwrap();
+    assert!(!viewshed.dirty);
+}
@@ -0,0 +1,116 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+mod common;
+use common::*;
+
+fn clock(game: &Game) -> (HungerState, i32) {
+    let player = *game.ecs.fetch::<Entity>();
+    let clocks = game.ecs.read_storage::<HungerClock>();
+    let clock = clocks.get(player).unwrap();
+    (clock.state, clock.duration)
+}
+
+fn set_clock(game: &mut Game, state: HungerState, duration: i32) {
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<HungerClock>()
+        .insert(player, HungerClock { state, duration })
+        .unwrap();
+}
+
+fn last_log(game: &Game) -> String {
+    let log = game.ecs.fetch::<gamelog::GameLog>();
+    log.entries.last().unwrap().text.clone()
+}
+
+#[test]
+fn the_player_starts_well_fed() {
+    let game = Game::new(41);
+    assert_eq!(clock(&game), (HungerState::WellFed, WELL_FED_TURNS));
+}
+
+#[test]
+fn the_clock_ticks_once_per_player_turn() {
+    let mut game = Game::new(41);
+    game.submit(Command::Wait);
+    game.submit(Command::Wait);
+    assert_eq!(clock(&game), (HungerState::WellFed, WELL_FED_TURNS - 2));
+
+    // Browsing the backpack is not a turn
+    game.submit(Command::ShowInventory);
+    game.submit(Command::Cancel);
+    assert_eq!(clock(&game), (HungerState::WellFed, WELL_FED_TURNS - 2));
+}
+
+#[test]
+fn hunger_sets_in_stage_by_stage() {
+    let mut game = Game::new(41);
+    set_clock(&mut game, HungerState::WellFed, 1);
+    game.submit(Command::Wait);
+    assert_eq!(clock(&game), (HungerState::Normal, NORMAL_TURNS));
+    assert_eq!(last_log(&game), "You are no longer well fed.");
+
+    set_clock(&mut game, HungerState::Normal, 1);
+    game.submit(Command::Wait);
+    assert_eq!(clock(&game), (HungerState::Hungry, HUNGRY_TURNS));
+    assert_eq!(last_log(&game), "You are hungry.");
+
+    set_clock(&mut game, HungerState::Hungry, 1);
+    game.submit(Command::Wait);
+    assert_eq!(clock(&game).0, HungerState::Starving);
+    assert_eq!(last_log(&game), "You are starving!");
+}
+
+#[test]
+fn starving_hurts_every_turn() {
+    let mut game = Game::new(41);
+    let player = *game.ecs.fetch::<Entity>();
+    set_clock(&mut game, HungerState::Starving, 0);
+    let before = hp(&game, player);
+
+    game.submit(Command::Wait);
+    game.submit(Command::Wait);
+    assert_eq!(hp(&game, player), before - 2);
+    assert_eq!(
+        last_log(&game),
+        "Your hunger pangs are getting painful! You suffer 1 hp damage."
+    );
+    assert_eq!(game.ecs.fetch::<RunStats>().damage_taken, 2);
+}
+
+#[test]
+fn hungry_fighters_hit_softer() {
+    let mut game = Game::new(41);
+    set_clock(&mut game, HungerState::Hungry, 100);
+    let monster = monster_next_to_player(&mut game);
+
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    // Player power 5, less 1 for hunger, against monster defense 1
+    assert_eq!(hp(&game, monster), 16 - 3);
+}
+
+#[test]
+fn eating_leaves_the_player_well_fed() {
+    let mut game = Game::new(41);
+    set_clock(&mut game, HungerState::Starving, 0);
+    let player_pos = *game.ecs.fetch::<Point>();
+    let rations = spawner::spawn_at(&mut game.ecs, "Rations", player_pos.x, player_pos.y).unwrap();
+    game.submit(Command::PickUp);
+
+    game.submit(Command::UseItem {
+        item: rations,
+        target: None,
+    });
+    assert_eq!(clock(&game).0, HungerState::WellFed);
+    assert!(!game.ecs.is_alive(rations));
+    let log = game.ecs.fetch::<gamelog::GameLog>();
+    assert!(log
+        .entries
+        .iter()
+        .any(|entry| entry.text == "You eat the Rations."));
+}
@@ -0,0 +1,262 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+mod common;
+use common::*;
+
+fn potion_under_player(game: &mut Game) -> Entity {
+    let player_pos = *game.ecs.fetch::<Point>();
+    spawner::health_potion(&mut game.ecs, player_pos.x, player_pos.y)
+}
+
+fn backpack_of(game: &Game, owner: Entity) -> Vec<Entity> {
+    let entities = game.ecs.entities();
+    let backpack = game.ecs.read_storage::<InBackpack>();
+    (&entities, &backpack)
+        .join()
+        .filter(|(_entity, pack)| pack.owner == owner)
+        .map(|(entity, _pack)| entity)
+        .collect()
+}
+
+#[test]
+fn picking_up_moves_the_item_into_the_backpack() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let potion = potion_under_player(&mut game);
+
+    game.submit(Command::PickUp);
+    assert_eq!(backpack_of(&game, player), vec![potion]);
+    assert!(game.ecs.read_storage::<Position>().get(potion).is_none());
+}
+
+#[test]
+fn picking_up_nothing_does_not_take_a_turn() {
+    let mut game = Game::new(51);
+    assert_eq!(game.tick(Some(Command::PickUp)), RunState::AwaitingInput);
+    let log = game.ecs.fetch::<gamelog::GameLog>();
+    assert_eq!(
+        log.entries.last().unwrap().text,
+        "There is nothing here to pick up."
+    );
+}
+
+#[test]
+fn drinking_a_potion_heals_and_consumes_it() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let potion = potion_under_player(&mut game);
+    game.submit(Command::PickUp);
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 10;
+
+    assert_eq!(
+        game.tick(Some(Command::ShowInventory)),
+        RunState::ShowInventory
+    );
+    game.submit(Command::UseItem {
+        item: potion,
+        target: None,
+    });
+    assert_eq!(
+        game.ecs
+            .read_storage::<CombatStats>()
+            .get(player)
+            .unwrap()
+            .hp,
+        18
+    );
+    assert!(!game.ecs.is_alive(potion));
+}
+
+#[test]
+fn dropping_puts_the_item_at_the_players_feet() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let potion = potion_under_player(&mut game);
+    game.submit(Command::PickUp);
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    let player_pos = *game.ecs.fetch::<Point>();
+
+    game.submit(Command::DropItem { item: potion });
+    assert!(backpack_of(&game, player).is_empty());
+    let positions = game.ecs.read_storage::<Position>();
+    let dropped = positions.get(potion).unwrap();
+    assert_eq!((dropped.x, dropped.y), (player_pos.x, player_pos.y));
+}
+
+#[test]
+fn menus_can_be_cancelled() {
+    let mut game = Game::new(51);
+    assert_eq!(
+        game.tick(Some(Command::ShowDropItem)),
+        RunState::ShowDropItem
+    );
+    assert_eq!(game.tick(None), RunState::ShowDropItem);
+    assert_eq!(game.tick(Some(Command::Cancel)), RunState::AwaitingInput);
+}
+
+/// A monster two tiles away from the player, inside the starting room.
+fn monster_in_sight(game: &mut Game) -> (Entity, Point) {
+    let player_pos = *game.ecs.fetch::<Point>();
+    let target = Point::new(player_pos.x + 2, player_pos.y);
+    let monster = spawner::random_monster(&mut game.ecs, target.x, target.y).unwrap();
+    (monster, target)
+}
+
+#[test]
+fn ranged_items_ask_for_a_target() {
+    let mut game = Game::new(51);
+    let scroll = carry(&mut game, spawner::magic_missile_scroll);
+
+    let state = game.submit(Command::UseItem {
+        item: scroll,
+        target: None,
+    });
+    assert_eq!(
+        state,
+        RunState::ShowTargeting {
+            range: 6,
+            item: scroll
+        }
+    );
+
+    // Out of range targets are refused
+    let player_pos = *game.ecs.fetch::<Point>();
+    let far_away = Point::new(player_pos.x + 20, player_pos.y);
+    let state = game.submit(Command::UseItem {
+        item: scroll,
+        target: Some(far_away),
+    });
+    assert_eq!(
+        state,
+        RunState::ShowTargeting {
+            range: 6,
+            item: scroll
+        }
+    );
+    assert!(game.ecs.is_alive(scroll));
+}
+
+#[test]
+fn magic_missiles_damage_their_target() {
+    let mut game = Game::new(51);
+    let scroll = carry(&mut game, spawner::magic_missile_scroll);
+    let (monster, target) = monster_in_sight(&mut game);
+
+    game.submit(Command::UseItem {
+        item: scroll,
+        target: Some(target),
+    });
+    assert_eq!(hp(&game, monster), 16 - 8);
+    assert!(!game.ecs.is_alive(scroll));
+}
+
+#[test]
+fn fireballs_hit_everything_in_the_blast() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let scroll = carry(&mut game, spawner::fireball_scroll);
+    let (monster, target) = monster_in_sight(&mut game);
+    let bystander = spawner::random_monster(&mut game.ecs, target.x, target.y + 1).unwrap();
+
+    game.submit(Command::UseItem {
+        item: scroll,
+        target: Some(target),
+    });
+    assert!(!game.ecs.is_alive(monster));
+    assert!(!game.ecs.is_alive(bystander));
+    // The player stood within the radius too
+    assert!(hp(&game, player) <= 30 - 20);
+}
+
+#[test]
+fn confused_monsters_lose_their_turns() {
+    let mut game = Game::new(51);
+    let scroll = carry(&mut game, spawner::confusion_scroll);
+    let (monster, target) = monster_in_sight(&mut game);
+
+    game.submit(Command::UseItem {
+        item: scroll,
+        target: Some(target),
+    });
+    for _ in 0..3 {
+        game.submit(Command::Wait);
+        let positions = game.ecs.read_storage::<Position>();
+        let pos = positions.get(monster).unwrap();
+        assert_eq!(Point::new(pos.x, pos.y), target);
+    }
+    assert!(game.ecs.read_storage::<Confusion>().get(monster).is_none());
+}
+
+#[test]
+fn only_carried_items_can_be_used_or_dropped() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let player_pos = *game.ecs.fetch::<Point>();
+    let on_the_floor = spawner::health_potion(&mut game.ecs, player_pos.x + 1, player_pos.y);
+    let (monster, target) = monster_in_sight(&mut game);
+
+    assert_eq!(
+        game.submit(Command::UseItem {
+            item: on_the_floor,
+            target: None,
+        }),
+        RunState::AwaitingInput
+    );
+    assert!(game.ecs.is_alive(on_the_floor));
+
+    assert_eq!(
+        game.submit(Command::DropItem { item: monster }),
+        RunState::AwaitingInput
+    );
+    let positions = game.ecs.read_storage::<Position>();
+    let monster_pos = positions.get(monster).unwrap();
+    assert_eq!((monster_pos.x, monster_pos.y), (target.x, target.y));
+    drop(positions);
+
+    // A potion already drunk is gone for good
+    let potion = potion_under_player(&mut game);
+    game.submit(Command::PickUp);
+    game.submit(Command::UseItem {
+        item: potion,
+        target: None,
+    });
+    assert_eq!(
+        game.submit(Command::UseItem {
+            item: potion,
+            target: None,
+        }),
+        RunState::AwaitingInput
+    );
+    assert!(backpack_of(&game, player).is_empty());
+}
+
+#[test]
+fn only_equipped_items_can_be_removed() {
+    let mut game = Game::new(51);
+    let player = *game.ecs.fetch::<Entity>();
+    let player_pos = *game.ecs.fetch::<Point>();
+    let on_the_floor = spawner::shield(&mut game.ecs, player_pos.x + 1, player_pos.y);
+    let (monster, _target) = monster_in_sight(&mut game);
+
+    for item in [on_the_floor, monster] {
+        assert_eq!(
+            game.submit(Command::RemoveItem { item }),
+            RunState::AwaitingInput
+        );
+    }
+    assert!(backpack_of(&game, player).is_empty());
+    assert!(game
+        .ecs
+        .read_storage::<Position>()
+        .get(on_the_floor)
+        .is_some());
+}
@@ -0,0 +1,52 @@
+use rltk::VirtualKeyCode;
+use rust_roguelike::*;
+
+#[test]
+fn default_keymap_covers_all_movement_schemes() {
+    let keymap = Keymap::default();
+    let north_west = Some(Command::Move {
+        delta_x: -1,
+        delta_y: -1,
+    });
+    assert_eq!(keymap.command_for(VirtualKeyCode::Numpad7), north_west);
+    assert_eq!(keymap.command_for(VirtualKeyCode::Y), north_west);
+    assert_eq!(keymap.command_for(VirtualKeyCode::Home), north_west);
+    assert_eq!(
+        keymap.command_for(VirtualKeyCode::Right),
+        Some(Command::Move {
+            delta_x: 1,
+            delta_y: 0
+        })
+    );
+    assert_eq!(
+        keymap.command_for(VirtualKeyCode::Numpad5),
+        Some(Command::Wait)
+    );
+    assert_eq!(keymap.command_for(VirtualKeyCode::Z), None);
+}
+
+#[test]
+fn keymaps_can_be_rebound() {
+    let keymap = Keymap::parse("# custom\nz = wait\n\nnumpad8 = south  # upside down\n").unwrap();
+    assert_eq!(keymap.command_for(VirtualKeyCode::Z), Some(Command::Wait));
+    assert_eq!(
+        keymap.command_for(VirtualKeyCode::Numpad8),
+        Some(Command::Move {
+            delta_x: 0,
+            delta_y: 1
+        })
+    );
+    assert_eq!(keymap.command_for(VirtualKeyCode::Up), None);
+}
+
+#[test]
+fn keymap_errors_name_the_line() {
+    let unknown_key = Keymap::parse("Up = north\nFoo = wait\n").err().unwrap();
+    assert_eq!(unknown_key, "line 2: unknown key 'Foo'");
+
+    let unknown_action = Keymap::parse("Up = fly").err().unwrap();
+    assert_eq!(unknown_action, "line 1: unknown action 'fly'");
+
+    let missing_action = Keymap::parse("\nUp").err().unwrap();
+    assert_eq!(missing_action, "line 2: expected '<key> = <action>'");
+}
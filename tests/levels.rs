@@ -0,0 +1,215 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn stairs(map: &Map) -> Vec<usize> {
+    map.tiles
+        .iter()
+        .enumerate()
+        .filter(|(_idx, tile)| **tile == TileType::DownStairs)
+        .map(|(idx, _tile)| idx)
+        .collect()
+}
+
+fn stand_on_stairs(game: &mut Game) {
+    let (x, y) = game
+        .ecs
+        .fetch::<Map>()
+        .find_tile(TileType::DownStairs)
+        .unwrap();
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+    game.ecs.insert(Point::new(x, y));
+}
+
+fn depth(game: &Game) -> i32 {
+    game.ecs.fetch::<Map>().depth
+}
+
+#[test]
+fn there_is_one_way_down() {
+    let game = Game::new(16);
+    let map = game.ecs.fetch::<Map>();
+    assert_eq!(stairs(&map).len(), 1);
+    assert_eq!(map.depth, 1);
+}
+
+#[test]
+fn descending_needs_stairs() {
+    let mut game = Game::new(16);
+    assert_eq!(game.submit(Command::Descend), RunState::AwaitingInput);
+    assert_eq!(depth(&game), 1);
+}
+
+#[test]
+fn descending_generates_a_deeper_level() {
+    let mut game = Game::new(16);
+    let first_level = game.ecs.fetch::<Map>().tiles.clone();
+    stand_on_stairs(&mut game);
+
+    assert_eq!(game.submit(Command::Descend), RunState::AwaitingInput);
+    assert_eq!(depth(&game), 2);
+    assert_ne!(game.ecs.fetch::<Map>().tiles, first_level);
+
+    let start = game.ecs.fetch::<Map>().find_tile(TileType::UpStairs);
+    let player_pos = *game.ecs.fetch::<Point>();
+    assert_eq!(Some((player_pos.x, player_pos.y)), start);
+}
+
+#[test]
+fn the_player_takes_only_what_they_carry() {
+    let mut game = Game::new(16);
+    let player = *game.ecs.fetch::<Entity>();
+    let player_pos = *game.ecs.fetch::<Point>();
+    let potion = spawner::health_potion(&mut game.ecs, player_pos.x, player_pos.y);
+    game.submit(Command::PickUp);
+    let dagger = spawner::dagger(&mut game.ecs, player_pos.x, player_pos.y);
+    game.submit(Command::PickUp);
+    game.submit(Command::UseItem {
+        item: dagger,
+        target: None,
+    });
+    let left_behind = spawner::shield(&mut game.ecs, player_pos.x, player_pos.y);
+    let old_monsters: Vec<Entity> = {
+        let entities = game.ecs.entities();
+        let monsters = game.ecs.read_storage::<Monster>();
+        (&entities, &monsters).join().map(|(e, _m)| e).collect()
+    };
+
+    stand_on_stairs(&mut game);
+    game.submit(Command::Descend);
+
+    assert!(game.ecs.is_alive(player));
+    assert_eq!(
+        game.ecs
+            .read_storage::<InBackpack>()
+            .get(potion)
+            .unwrap()
+            .owner,
+        player
+    );
+    assert_eq!(
+        game.ecs
+            .read_storage::<Equipped>()
+            .get(dagger)
+            .unwrap()
+            .owner,
+        player
+    );
+    // What stays behind waits, off the map, on the level it was left on
+    let positions = game.ecs.read_storage::<Position>();
+    let other_level = game.ecs.read_storage::<OtherLevelPosition>();
+    for entity in old_monsters.iter().chain([&left_behind]) {
+        assert!(positions.get(*entity).is_none());
+        assert_eq!(other_level.get(*entity).unwrap().depth, 1);
+    }
+}
+
+#[test]
+fn deeper_monsters_are_tougher() {
+    let mut game = Game::new(16);
+    let shallow = spawner::random_monster(&mut game.ecs, 1, 1).unwrap();
+    for _ in 0..4 {
+        stand_on_stairs(&mut game);
+        game.submit(Command::Descend);
+    }
+    assert_eq!(depth(&game), 5);
+    let deep = spawner::random_monster(&mut game.ecs, 1, 1).unwrap();
+
+    let stats = game.ecs.read_storage::<CombatStats>();
+    assert_eq!(stats.get(shallow).unwrap().max_hp, 16);
+    assert!(stats.get(deep).unwrap().max_hp > 16);
+    assert!(stats.get(deep).unwrap().power > 4);
+}
+
+fn stand_on_up_stairs(game: &mut Game) {
+    let (x, y) = game
+        .ecs
+        .fetch::<Map>()
+        .find_tile(TileType::UpStairs)
+        .unwrap();
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+    game.ecs.insert(Point::new(x, y));
+}
+
+struct LevelState {
+    tiles: Vec<TileType>,
+    revealed: Vec<bool>,
+    entities: Vec<(String, i32, i32)>,
+}
+
+fn level_state(game: &Game) -> LevelState {
+    let map = game.ecs.fetch::<Map>();
+    let names = game.ecs.read_storage::<Name>();
+    let positions = game.ecs.read_storage::<Position>();
+    let players = game.ecs.read_storage::<Player>();
+    let mut entities: Vec<(String, i32, i32)> = (&names, &positions, !&players)
+        .join()
+        .map(|(name, pos, _player)| (name.name.clone(), pos.x, pos.y))
+        .collect();
+    entities.sort();
+    LevelState {
+        tiles: map.tiles.clone(),
+        revealed: map.revealed_tiles.clone(),
+        entities,
+    }
+}
+
+#[test]
+fn only_deeper_levels_have_stairs_up() {
+    let mut game = Game::new(17);
+    assert_eq!(game.ecs.fetch::<Map>().find_tile(TileType::UpStairs), None);
+    assert_eq!(game.submit(Command::Ascend), RunState::AwaitingInput);
+    assert_eq!(depth(&game), 1);
+
+    stand_on_stairs(&mut game);
+    game.submit(Command::Descend);
+    let map = game.ecs.fetch::<Map>();
+    let player_pos = *game.ecs.fetch::<Point>();
+    assert_eq!(
+        map.find_tile(TileType::UpStairs),
+        Some((player_pos.x, player_pos.y))
+    );
+}
+
+#[test]
+fn going_back_up_restores_the_level_as_it_was_left() {
+    let mut game = Game::new(17);
+    game.submit(Command::Wait);
+    stand_on_stairs(&mut game);
+    let first_level = level_state(&game);
+
+    game.submit(Command::Descend);
+    let second_level = level_state(&game);
+    stand_on_up_stairs(&mut game);
+    assert_eq!(game.submit(Command::Ascend), RunState::AwaitingInput);
+
+    assert_eq!(depth(&game), 1);
+    let back = level_state(&game);
+    assert_eq!(back.tiles, first_level.tiles);
+    assert!(first_level
+        .revealed
+        .iter()
+        .zip(back.revealed.iter())
+        .all(|(before, after)| !before || *after));
+    assert_eq!(back.entities, first_level.entities);
+
+    // Arrived on the stairs down, which lead back to the same second level
+    let player_pos = *game.ecs.fetch::<Point>();
+    assert_eq!(
+        game.ecs.fetch::<Map>().find_tile(TileType::DownStairs),
+        Some((player_pos.x, player_pos.y))
+    );
+    game.submit(Command::Descend);
+    assert_eq!(depth(&game), 2);
+    let again = level_state(&game);
+    assert_eq!(again.tiles, second_level.tiles);
+    assert_eq!(again.entities, second_level.entities);
+}
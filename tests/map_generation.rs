@@ -0,0 +1,548 @@
+use rltk::RandomNumberGenerator;
+use rust_roguelike::map_builders::*;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn rooms_and_corridors(width: i32, height: i32, rng: &mut RandomNumberGenerator) -> Map {
+    let mut builder = ChainSpec::classic().builder(width, height, 1);
+    builder.build(rng);
+    builder.get_map()
+}
+
+#[test]
+fn the_same_seed_generates_the_same_map() {
+    for seed in [0, 1, 42, 0xdead_beef] {
+        let first = rooms_and_corridors(80, 50, &mut RandomNumberGenerator::seeded(seed));
+        let second = rooms_and_corridors(80, 50, &mut RandomNumberGenerator::seeded(seed));
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.rooms, second.rooms);
+    }
+}
+
+#[test]
+fn different_seeds_generate_different_maps() {
+    let first = rooms_and_corridors(80, 50, &mut RandomNumberGenerator::seeded(1));
+    let second = rooms_and_corridors(80, 50, &mut RandomNumberGenerator::seeded(2));
+    assert_ne!(first.tiles, second.tiles);
+}
+
+#[test]
+fn games_with_the_same_seed_start_identically() {
+    let first = Game::new(7);
+    let second = Game::new(7);
+    assert_eq!(first.seed(), 7);
+    assert_eq!(
+        first.ecs.fetch::<Map>().tiles,
+        second.ecs.fetch::<Map>().tiles
+    );
+    assert_eq!(
+        first.ecs.fetch::<Map>().rooms,
+        second.ecs.fetch::<Map>().rooms
+    );
+}
+
+#[test]
+fn maps_of_different_sizes_coexist() {
+    let mut rng = RandomNumberGenerator::seeded(3);
+    let town = rooms_and_corridors(40, 30, &mut rng);
+    let dungeon = rooms_and_corridors(160, 100, &mut rng);
+
+    assert_eq!(town.tiles.len(), 40 * 30);
+    assert_eq!(dungeon.tiles.len(), 160 * 100);
+    assert_eq!(dungeon.xy_idx(159, 99), dungeon.tiles.len() - 1);
+    for room in dungeon.rooms.iter() {
+        assert!(dungeon.in_bounds(room.x2, room.y2));
+    }
+    assert!(dungeon
+        .rooms
+        .iter()
+        .any(|room| room.x2 >= 80 || room.y2 >= 50));
+}
+
+/// One chain for each initial builder, and some that mix in meta-builders.
+fn every_builder(depth: i32) -> Vec<(String, BuilderChain)> {
+    use InitialStep::*;
+    use MetaStep::*;
+    let specs = vec![
+        (SimpleMap, vec![DoglegCorridors]),
+        (BspDungeon, vec![BspCorridors]),
+        (BspInterior, vec![BspCorridors, Doors]),
+        (CellularAutomata, vec![]),
+        (DrunkardsWalk, vec![Vaults]),
+        (Maze, vec![]),
+        (Dla, vec![]),
+        (Voronoi, vec![]),
+        (CellularAutomata, vec![WaveformCollapse]),
+        (Voronoi, vec![PrefabSection]),
+        (
+            SimpleMap,
+            vec![RoomExploder, NearestCorridors, Doors, Vaults],
+        ),
+        (
+            BspDungeon,
+            vec![RoundedRoomCorners, DijkstraCorridors, Doors],
+        ),
+    ];
+    specs
+        .into_iter()
+        .map(|(start, steps)| {
+            let spec = ChainSpec { start, steps };
+            let name = format!("{:?}", spec);
+            (name, spec.builder(MAP_WIDTH, MAP_HEIGHT, depth))
+        })
+        .collect()
+}
+
+#[test]
+fn every_builder_digs_a_start_and_one_way_down() {
+    for (name, mut builder) in every_builder(3) {
+        builder.build(&mut RandomNumberGenerator::seeded(18));
+        let map = builder.get_map();
+        let start = builder.get_starting_position();
+
+        assert_eq!(map.depth, 3, "{}", name);
+        assert_eq!(
+            map.tiles[map.xy_idx(start.x, start.y)],
+            TileType::Floor,
+            "{}",
+            name
+        );
+        let stairs = map
+            .tiles
+            .iter()
+            .filter(|tile| **tile == TileType::DownStairs)
+            .count();
+        assert_eq!(stairs, 1, "{}", name);
+        for x in 0..map.width {
+            assert_eq!(map.tiles[map.xy_idx(x, 0)], TileType::Wall, "{}", name);
+            assert_eq!(
+                map.tiles[map.xy_idx(x, map.height - 1)],
+                TileType::Wall,
+                "{}",
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn every_builder_is_reproducible_from_its_seed() {
+    for ((name, mut first), (_name, mut second)) in
+        every_builder(2).into_iter().zip(every_builder(2))
+    {
+        first.build(&mut RandomNumberGenerator::seeded(5));
+        second.build(&mut RandomNumberGenerator::seeded(5));
+        assert_eq!(first.get_map().tiles, second.get_map().tiles, "{}", name);
+    }
+}
+
+#[test]
+fn the_first_level_is_rooms_and_corridors() {
+    let game = Game::new(18);
+    let map = game.ecs.fetch::<Map>();
+    assert!(!map.rooms.is_empty());
+}
+
+#[test]
+fn builders_record_each_step_ending_with_the_finished_map() {
+    for (name, mut builder) in every_builder(2) {
+        builder.build(&mut RandomNumberGenerator::seeded(19));
+        let history = builder.get_snapshot_history();
+        assert!(history.len() > 1, "{}", name);
+        assert_eq!(history.last(), Some(&builder.get_map().tiles), "{}", name);
+    }
+}
+
+#[test]
+fn corridors_are_snapshotted_as_they_are_dug() {
+    let mut builder = ChainSpec::classic().builder(MAP_WIDTH, MAP_HEIGHT, 1);
+    builder.build(&mut RandomNumberGenerator::seeded(19));
+    let rooms = builder.get_map().rooms.len();
+    // One snapshot per room, two per corridor and one for the stairs
+    assert_eq!(
+        builder.get_snapshot_history().len(),
+        rooms + 2 * (rooms - 1) + 1
+    );
+}
+
+fn visualized_game(seed: u64) -> Game {
+    let mut game = Game::new(seed).with_settings(Settings {
+        show_mapgen: true,
+        ..Settings::default()
+    });
+    game.open_main_menu();
+    game
+}
+
+#[test]
+fn a_new_game_replays_its_generation_when_asked() {
+    let mut game = visualized_game(19);
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::NewGame))),
+        RunState::MapGeneration { step: 0 }
+    );
+    let final_tiles = game.ecs.fetch::<Map>().tiles.clone();
+
+    let mut last = Vec::new();
+    let mut steps = 0;
+    while let Some(tiles) = game.mapgen_snapshot() {
+        last = tiles.to_vec();
+        steps += 1;
+        game.tick(None);
+    }
+    assert!(steps > 1);
+    assert_eq!(last, final_tiles);
+    assert_eq!(game.run_state(), RunState::PreRun);
+    assert_eq!(game.tick(None), RunState::AwaitingInput);
+}
+
+#[test]
+fn the_replay_can_be_skipped() {
+    let mut game = visualized_game(19);
+    game.tick(Some(Command::Choose(MenuOption::NewGame)));
+    assert_eq!(game.tick(Some(Command::Cancel)), RunState::PreRun);
+    assert_eq!(game.mapgen_snapshot(), None);
+}
+
+#[test]
+fn without_the_flag_play_starts_straight_away() {
+    let mut game = Game::new(19);
+    game.open_main_menu();
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::NewGame))),
+        RunState::PreRun
+    );
+}
+
+/// Steps from `start` to every tile that can be walked to; `None` for the rest.
+fn walking_distances(map: &Map, start: Position) -> Vec<Option<usize>> {
+    let mut distances = vec![None; map.tiles.len()];
+    let mut frontier = std::collections::VecDeque::new();
+    distances[map.xy_idx(start.x, start.y)] = Some(0);
+    frontier.push_back((start.x, start.y));
+    while let Some((x, y)) = frontier.pop_front() {
+        let here = distances[map.xy_idx(x, y)].unwrap();
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if !map.in_bounds(nx, ny) {
+                continue;
+            }
+            let idx = map.xy_idx(nx, ny);
+            if map.tiles[idx] != TileType::Wall && distances[idx].is_none() {
+                distances[idx] = Some(here + 1);
+                frontier.push_back((nx, ny));
+            }
+        }
+    }
+    distances
+}
+
+#[test]
+fn every_generated_map_is_fully_connected() {
+    for seed in 0..3 {
+        for (name, mut builder) in every_builder(2) {
+            builder.build(&mut RandomNumberGenerator::seeded(seed));
+            let map = builder.get_map();
+            let distances = walking_distances(&map, builder.get_starting_position());
+
+            for (idx, tile) in map.tiles.iter().enumerate() {
+                if *tile != TileType::Wall {
+                    assert!(
+                        distances[idx].is_some(),
+                        "{} (seed {}) cannot reach {}",
+                        name,
+                        seed,
+                        idx
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn the_exit_is_the_farthest_reachable_tile() {
+    for (name, mut builder) in every_builder(2) {
+        builder.build(&mut RandomNumberGenerator::seeded(20));
+        let mut map = builder.get_map();
+        map.populate_blocked();
+        let start = builder.get_starting_position();
+        let start_idx = map.xy_idx(start.x, start.y);
+        let max_depth = map.tiles.len() as f32;
+        let dijkstra = rltk::DijkstraMap::new(map.width, map.height, &[start_idx], &map, max_depth);
+
+        let (x, y) = map.find_tile(TileType::DownStairs).unwrap();
+        let farthest = dijkstra
+            .map
+            .iter()
+            .filter(|distance| **distance < f32::MAX)
+            .fold(0.0f32, |a, b| a.max(*b));
+        assert_eq!(dijkstra.map[map.xy_idx(x, y)], farthest, "{}", name);
+    }
+}
+
+#[test]
+fn every_corridor_style_reaches_every_room() {
+    use MetaStep::*;
+    for corridors in [
+        DoglegCorridors,
+        BspCorridors,
+        NearestCorridors,
+        DijkstraCorridors,
+    ] {
+        let spec = ChainSpec {
+            start: InitialStep::SimpleMap,
+            steps: vec![corridors],
+        };
+        let mut builder = spec.builder(MAP_WIDTH, MAP_HEIGHT, 2);
+        builder.build(&mut RandomNumberGenerator::seeded(21));
+        let map = builder.get_map();
+        assert!(map.rooms.len() > 1);
+        // Culling would have walled over any room left out
+        for room in map.rooms.iter() {
+            let (x, y) = room.center();
+            assert_ne!(
+                map.tiles[map.xy_idx(x, y)],
+                TileType::Wall,
+                "{:?}",
+                corridors
+            );
+        }
+    }
+}
+
+#[test]
+fn doors_hang_where_corridors_meet_rooms() {
+    let spec = ChainSpec {
+        start: InitialStep::BspDungeon,
+        steps: vec![MetaStep::BspCorridors, MetaStep::Doors],
+    };
+    let mut builder = spec.builder(MAP_WIDTH, MAP_HEIGHT, 2);
+    builder.build(&mut RandomNumberGenerator::seeded(21));
+    let map = builder.get_map();
+    let doors: Vec<usize> = builder
+        .build_data
+        .spawn_list
+        .iter()
+        .filter(|(_idx, name)| name == "Door")
+        .map(|(idx, _name)| *idx)
+        .collect();
+    assert!(!doors.is_empty());
+
+    let in_a_room = |x: i32, y: i32| {
+        map.rooms
+            .iter()
+            .any(|room| x > room.x1 && x <= room.x2 && y > room.y1 && y <= room.y2)
+    };
+    for idx in doors {
+        let (x, y) = (idx as i32 % map.width, idx as i32 / map.width);
+        assert!(!in_a_room(x, y));
+        assert!([(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .iter()
+            .any(|(dx, dy)| in_a_room(x + dx, y + dy)));
+    }
+}
+
+#[test]
+fn vaults_bring_their_own_inhabitants() {
+    let spec = ChainSpec {
+        start: InitialStep::DrunkardsWalk,
+        steps: vec![MetaStep::Vaults],
+    };
+    let mut builder = spec.builder(MAP_WIDTH, MAP_HEIGHT, 5);
+    builder.build(&mut RandomNumberGenerator::seeded(21));
+    let map = builder.get_map();
+    let spawns = &builder.build_data.spawn_list;
+    assert!(spawns.iter().any(|(_idx, name)| name == "Health Potion"));
+    for (idx, _name) in spawns.iter() {
+        assert_eq!(map.tiles[*idx], TileType::Floor);
+    }
+}
+
+#[test]
+fn chains_can_be_written_down_as_data() {
+    let spec = ChainSpec {
+        start: InitialStep::BspDungeon,
+        steps: vec![
+            MetaStep::RoundedRoomCorners,
+            MetaStep::NearestCorridors,
+            MetaStep::Doors,
+        ],
+    };
+    let json = serde_json::to_string(&spec).unwrap();
+    let parsed: ChainSpec = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, spec);
+
+    let mut first = spec.builder(MAP_WIDTH, MAP_HEIGHT, 2);
+    let mut second = parsed.builder(MAP_WIDTH, MAP_HEIGHT, 2);
+    first.build(&mut RandomNumberGenerator::seeded(21));
+    second.build(&mut RandomNumberGenerator::seeded(21));
+    assert_eq!(first.get_map().tiles, second.get_map().tiles);
+}
+
+#[test]
+fn the_first_level_always_uses_the_classic_chain() {
+    let mut rng = RandomNumberGenerator::seeded(21);
+    assert_eq!(random_spec(1, &mut rng), ChainSpec::classic());
+}
+
+#[test]
+fn rex_paint_layers_stack_in_order() {
+    let prefab = load_rex_prefab(include_bytes!("../resources/mltest.xp"));
+    assert_eq!((prefab.width, prefab.height), (8, 4));
+    for y in 0..4 {
+        for x in 0..8 {
+            // The second layer draws over the middle and is transparent elsewhere
+            let expected = if (2..6).contains(&x) && (1..3).contains(&y) {
+                'B'
+            } else {
+                'A'
+            };
+            assert_eq!(prefab.glyphs[x + y * 8], expected, "({}, {})", x, y);
+        }
+    }
+
+    let nyan = load_rex_prefab(include_bytes!("../resources/nyan.xp"));
+    assert_eq!((nyan.width, nyan.height), (35, 22));
+    assert_eq!(nyan.glyphs.len(), 35 * 22);
+    assert!(nyan.glyphs.contains(&'█'));
+}
+
+#[test]
+fn a_prefab_level_keeps_its_own_start_and_stairs() {
+    let prefab = load_rex_prefab(CRYPT.template);
+    let marker = |glyph: char| {
+        let idx = prefab.glyphs.iter().position(|ch| *ch == glyph).unwrap();
+        let x = (idx % prefab.width) as i32 + (MAP_WIDTH - prefab.width as i32) / 2;
+        let y = (idx / prefab.width) as i32 + (MAP_HEIGHT - prefab.height as i32) / 2;
+        (x, y)
+    };
+
+    let spec = ChainSpec {
+        start: InitialStep::PrefabLevel,
+        steps: vec![],
+    };
+    let mut builder = spec.builder(MAP_WIDTH, MAP_HEIGHT, 4);
+    builder.build(&mut RandomNumberGenerator::seeded(22));
+    let map = builder.get_map();
+    let start = builder.get_starting_position();
+
+    assert_eq!((start.x, start.y), marker('@'));
+    assert_eq!(map.find_tile(TileType::DownStairs), Some(marker('>')));
+    assert_eq!(
+        map.tiles
+            .iter()
+            .filter(|tile| **tile == TileType::DownStairs)
+            .count(),
+        1
+    );
+    let spawns = &builder.build_data.spawn_list;
+    for name in ["Goblin", "Orc", "Door", "Shield"] {
+        assert!(spawns.iter().any(|(_idx, spawn)| spawn == name), "{}", name);
+    }
+    let distances = walking_distances(&map, start);
+    let (x, y) = marker('>');
+    assert!(distances[map.xy_idx(x, y)].is_some());
+}
+
+#[test]
+fn sections_are_stamped_against_their_edge() {
+    let prefab = load_rex_prefab(GATEHOUSE.template);
+    let spec = ChainSpec {
+        start: InitialStep::CellularAutomata,
+        steps: vec![MetaStep::PrefabSection],
+    };
+    let mut builder = spec.builder(MAP_WIDTH, MAP_HEIGHT, 3);
+    builder.build(&mut RandomNumberGenerator::seeded(22));
+    let map = builder.get_map();
+
+    let left = MAP_WIDTH - 1 - prefab.width as i32;
+    let top = (MAP_HEIGHT - prefab.height as i32) / 2;
+    for ty in 0..prefab.height {
+        for tx in 0..prefab.width {
+            let idx = map.xy_idx(left + tx as i32, top + ty as i32);
+            if prefab.glyphs[tx + ty * prefab.width] == '█' {
+                assert_eq!(map.tiles[idx], TileType::Wall);
+            }
+        }
+    }
+    let start = builder.get_starting_position();
+    assert!(start.x < left || start.y < top || start.y >= top + prefab.height as i32);
+    // The orcs on guard are reachable, so they survive the culling
+    let orcs = builder
+        .build_data
+        .spawn_list
+        .iter()
+        .filter(|(idx, name)| name == "Orc" && *idx as i32 % MAP_WIDTH >= left)
+        .count();
+    assert_eq!(orcs, 2);
+}
+
+/// A builder that digs nothing at all.
+struct SolidRock;
+
+impl InitialMapBuilder for SolidRock {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, _build_data: &mut BuilderMap) {}
+}
+
+#[test]
+fn a_map_with_no_floor_still_gets_a_start() {
+    let mut builder = BuilderChain::new(20, 10, 1).start_with(Box::new(SolidRock));
+    builder.build(&mut RandomNumberGenerator::seeded(18));
+    let map = builder.get_map();
+    let start = builder.get_starting_position();
+
+    assert_eq!((start.x, start.y), (10, 5));
+    assert_ne!(map.tiles[map.xy_idx(start.x, start.y)], TileType::Wall);
+}
+
+#[test]
+fn a_start_with_nowhere_to_go_gets_stairs_beside_it() {
+    let mut builder = BuilderChain::new(20, 10, 3).start_with(Box::new(SolidRock));
+    builder.build(&mut RandomNumberGenerator::seeded(18));
+    let map = builder.get_map();
+    let start = builder.get_starting_position();
+
+    // The start stays floor, so the way up can go there without covering the way down
+    assert_eq!(map.tiles[map.xy_idx(start.x, start.y)], TileType::Floor);
+    let (x, y) = map.find_tile(TileType::DownStairs).unwrap();
+    assert_eq!((start.x - x).abs() + (start.y - y).abs(), 1);
+}
+
+/// A builder that digs just a start and the tile beside it, which becomes the way down.
+struct OneStep;
+
+impl InitialMapBuilder for OneStep {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        for x in [10, 11] {
+            let idx = build_data.map.xy_idx(x, 5);
+            build_data.map.tiles[idx] = TileType::Floor;
+        }
+        build_data.starting_position = Some(Position { x: 10, y: 5 });
+    }
+}
+
+#[test]
+fn nothing_is_spawned_on_the_stairs_up() {
+    for seed in 0..20 {
+        let mut game = Game::new(seed);
+        let mut builder = BuilderChain::new(20, 10, 3).start_with(Box::new(OneStep));
+        builder.build(&mut RandomNumberGenerator::seeded(seed));
+        let mut map = builder.get_map();
+        let start = builder.get_starting_position();
+        let start_idx = map.xy_idx(start.x, start.y);
+        map.tiles[start_idx] = TileType::UpStairs;
+        game.ecs.insert(map);
+        builder.spawn_entities(&mut game.ecs);
+
+        let positions = game.ecs.read_storage::<Position>();
+        let items = game.ecs.read_storage::<Item>();
+        assert!(
+            !(&positions, &items)
+                .join()
+                .any(|(pos, _item)| (pos.x, pos.y) == (start.x, start.y)),
+            "seed {}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn player_entity(game: &Game) -> Entity {
+    let entities = game.ecs.entities();
+    let players = game.ecs.read_storage::<Player>();
+    (&entities, &players).join().next().unwrap().0
+}
+
+#[test]
+fn tile_content_lists_what_stands_on_each_tile() {
+    let game = Game::new(21);
+    let player = player_entity(&game);
+    let player_pos = *game.ecs.fetch::<Point>();
+    let map = game.ecs.fetch::<Map>();
+
+    let idx = map.xy_idx(player_pos.x, player_pos.y);
+    assert_eq!(map.tile_content[idx], vec![player]);
+
+    let positions = game.ecs.read_storage::<Position>();
+    let monsters = game.ecs.read_storage::<Monster>();
+    let entities = game.ecs.entities();
+    for (entity, pos, _monster) in (&entities, &positions, &monsters).join() {
+        let idx = map.xy_idx(pos.x, pos.y);
+        assert!(map.tile_content[idx].contains(&entity));
+        assert!(map.blocked[idx]);
+    }
+}
+
+#[test]
+fn monsters_block_the_player() {
+    let mut game = Game::new(21);
+    let player_pos = *game.ecs.fetch::<Point>();
+    spawner::random_monster(&mut game.ecs, player_pos.x + 1, player_pos.y).unwrap();
+    game.submit(Command::Wait);
+
+    let before = *game.ecs.fetch::<Point>();
+    try_move_player(1, 0, &mut game.ecs);
+    assert_eq!(*game.ecs.fetch::<Point>(), before);
+}
+
+#[test]
+fn monsters_never_share_a_tile() {
+    let mut game = Game::new(21);
+    let room = game.ecs.fetch::<Map>().rooms[0];
+    for x in room.x1 + 1..room.x1 + 4 {
+        spawner::random_monster(&mut game.ecs, x, room.y1 + 1).unwrap();
+    }
+
+    for _ in 0..10 {
+        game.submit(Command::Wait);
+        let positions = game.ecs.read_storage::<Position>();
+        let monsters = game.ecs.read_storage::<Monster>();
+        let mut occupied: Vec<(i32, i32)> = (&positions, &monsters)
+            .join()
+            .map(|(pos, _monster)| (pos.x, pos.y))
+            .collect();
+        let count = occupied.len();
+        occupied.sort_unstable();
+        occupied.dedup();
+        assert_eq!(occupied.len(), count);
+    }
+}
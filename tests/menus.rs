@@ -0,0 +1,160 @@
+use rust_roguelike::*;
+use specs::prelude::*;
+use std::path::PathBuf;
+
+fn save_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rust_roguelike_menus_{}.json", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn kill_player(game: &mut Game) {
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 0;
+}
+
+#[test]
+fn the_main_menu_starts_a_new_game() {
+    let mut game = Game::new(15).with_save_file(save_path("new_game"));
+    game.open_main_menu();
+    assert_eq!(
+        game.menu_options(),
+        vec![MenuOption::NewGame, MenuOption::Options, MenuOption::Quit]
+    );
+
+    game.tick(Some(Command::Highlight(MenuOption::Options)));
+    assert_eq!(
+        game.run_state(),
+        RunState::MainMenu {
+            selection: MenuOption::Options
+        }
+    );
+    game.tick(Some(Command::Choose(MenuOption::NewGame)));
+    assert_eq!(game.tick(None), RunState::AwaitingInput);
+}
+
+#[test]
+fn a_save_that_will_not_load_is_reported_on_the_main_menu() {
+    let path = save_path("corrupt");
+    std::fs::write(&path, "not a save").unwrap();
+    let mut game = Game::new(15).with_save_file(&path);
+    // A red line in the log is not a menu error
+    game.ecs
+        .write_resource::<gamelog::GameLog>()
+        .push("You are starving!", rltk::RGB::named(rltk::RED));
+    game.open_main_menu();
+    assert_eq!(game.last_error(), None);
+
+    let before = game.run_state();
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Continue))),
+        before
+    );
+    assert!(game.last_error().unwrap().starts_with("Unable to load"));
+
+    game.tick(Some(Command::Choose(MenuOption::NewGame)));
+    assert_eq!(game.last_error(), None);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn entries_that_are_not_on_show_cannot_be_chosen() {
+    let mut game = Game::new(15).with_save_file(save_path("hidden"));
+    game.open_main_menu();
+    let before = game.run_state();
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Continue))),
+        before
+    );
+    assert_eq!(game.tick(Some(Command::Cancel)), before);
+}
+
+#[test]
+fn the_pause_menu_resumes_without_spending_a_turn() {
+    let mut game = Game::new(15);
+    assert_eq!(
+        game.tick(Some(Command::ShowPauseMenu)),
+        RunState::PauseMenu {
+            selection: MenuOption::Resume
+        }
+    );
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Resume))),
+        RunState::AwaitingInput
+    );
+
+    game.tick(Some(Command::ShowPauseMenu));
+    assert_eq!(game.tick(Some(Command::Cancel)), RunState::AwaitingInput);
+    assert_eq!(game.ecs.fetch::<RunStats>().turns, 0);
+}
+
+#[test]
+fn options_return_to_the_menu_they_were_opened_from() {
+    let mut game = Game::new(15);
+    game.tick(Some(Command::ShowPauseMenu));
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Options))),
+        RunState::OptionsMenu {
+            selection: MenuOption::TogglePermadeath,
+            paused: true
+        }
+    );
+    assert_eq!(
+        game.tick(Some(Command::Cancel)),
+        RunState::PauseMenu {
+            selection: MenuOption::Options
+        }
+    );
+
+    game.open_main_menu();
+    game.tick(Some(Command::Choose(MenuOption::Options)));
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Back))),
+        RunState::MainMenu {
+            selection: MenuOption::Options
+        }
+    );
+}
+
+#[test]
+fn turning_permadeath_off_keeps_the_save() {
+    let path = save_path("permadeath_off");
+    let mut game = Game::new(15).with_save_file(&path);
+    game.tick(Some(Command::ShowPauseMenu));
+    game.tick(Some(Command::Choose(MenuOption::Options)));
+    game.tick(Some(Command::Choose(MenuOption::TogglePermadeath)));
+    assert!(!game.settings().permadeath);
+    game.tick(Some(Command::Cancel));
+    game.tick(Some(Command::Cancel));
+
+    game.save_to_file(&path).unwrap();
+    kill_player(&mut game);
+    assert_eq!(game.submit(Command::Wait), RunState::GameOver);
+    assert!(path.exists());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn the_game_over_screen_counts_the_run_and_leads_back_to_the_menu() {
+    let mut game = Game::new(15).with_save_file(save_path("game_over"));
+    game.submit(Command::Wait);
+    game.submit(Command::Wait);
+    kill_player(&mut game);
+    assert_eq!(game.submit(Command::Wait), RunState::GameOver);
+    assert_eq!(game.ecs.fetch::<RunStats>().turns, 2);
+    assert_eq!(game.menu_options(), vec![MenuOption::MainMenu]);
+
+    let old_seed = game.seed();
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::MainMenu))),
+        RunState::MainMenu {
+            selection: MenuOption::NewGame
+        }
+    );
+    assert_ne!(game.seed(), old_seed);
+    assert_eq!(*game.ecs.fetch::<RunStats>(), RunStats::default());
+}
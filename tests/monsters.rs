@@ -0,0 +1,65 @@
+use rltk::{BaseMap, DistanceAlg, Point};
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn distance_to_player(game: &Game, monster: Entity) -> f32 {
+    let positions = game.ecs.read_storage::<Position>();
+    let pos = positions.get(monster).unwrap();
+    let player = *game.ecs.fetch::<Point>();
+    DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), player)
+}
+
+#[test]
+fn every_room_but_the_first_gets_a_monster() {
+    let game = Game::new(11);
+    let map = game.ecs.fetch::<Map>();
+    let positions = game.ecs.read_storage::<Position>();
+    let monsters = game.ecs.read_storage::<Monster>();
+
+    let monster_count = (&positions, &monsters).join().count();
+    assert_eq!(monster_count, map.rooms.len() - 1);
+
+    let first_room = map.rooms[0];
+    for (pos, _monster) in (&positions, &monsters).join() {
+        let inside_first_room = pos.x > first_room.x1
+            && pos.x <= first_room.x2
+            && pos.y > first_room.y1
+            && pos.y <= first_room.y2;
+        assert!(!inside_first_room);
+    }
+}
+
+#[test]
+fn monsters_that_see_the_player_close_in() {
+    let mut game = Game::new(11);
+    let corner = {
+        let room = game.ecs.fetch::<Map>().rooms[0];
+        (room.x1 + 1, room.y1 + 1)
+    };
+    let monster = spawner::random_monster(&mut game.ecs, corner.0, corner.1).unwrap();
+
+    let before = distance_to_player(&game, monster);
+    game.submit(Command::Wait);
+    let after = distance_to_player(&game, monster);
+    assert!(after < before);
+
+    for _ in 0..10 {
+        game.submit(Command::Wait);
+    }
+    assert!(distance_to_player(&game, monster) < 1.5);
+}
+
+#[test]
+fn pathing_never_leads_into_walls() {
+    let game = Game::new(11);
+    let map = game.ecs.fetch::<Map>();
+    let (x, y) = map.rooms[0].center();
+    let exits = map.get_available_exits(map.xy_idx(x, y));
+    assert_eq!(exits.len(), 8);
+
+    for idx in 0..map.tiles.len() {
+        for (exit, _cost) in map.get_available_exits(idx) {
+            assert_ne!(map.tiles[exit], TileType::Wall);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use rltk::{BaseMap, Point};
+use rust_roguelike::*;
+use specs::prelude::*;
+
+/// A 5x5 open room with a single wall pillar in the middle of the top edge.
+fn open_map() -> Map {
+    let mut map = Map::new(7, 7);
+    for y in 1..6 {
+        for x in 1..6 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+    map.populate_blocked();
+    map
+}
+
+#[test]
+fn diagonal_steps_cannot_cut_wall_corners() {
+    let mut map = open_map();
+    assert!(!map.cuts_wall_corner(3, 3, 1, 1));
+    assert!(map.cuts_wall_corner(1, 1, -1, -1));
+
+    // Two walls meeting at a corner block the diagonal between them
+    for (x, y) in [(3, 2), (2, 3)] {
+        let idx = map.xy_idx(x, y);
+        map.tiles[idx] = TileType::Wall;
+    }
+    map.populate_blocked();
+    assert!(map.cuts_wall_corner(2, 2, 1, 1));
+    assert!(!map.cuts_wall_corner(2, 2, 1, -1));
+
+    let exits = map.get_available_exits(map.xy_idx(2, 2));
+    assert!(!exits.iter().any(|(idx, _cost)| *idx == map.xy_idx(3, 3)));
+}
+
+#[test]
+fn the_player_moves_diagonally() {
+    let mut game = Game::new(41);
+    let start = *game.ecs.fetch::<Point>();
+    let (delta_x, delta_y) = {
+        let map = game.ecs.fetch::<Map>();
+        [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+            .into_iter()
+            .find(|(dx, dy)| !map.blocked[map.xy_idx(start.x + dx, start.y + dy)])
+            .unwrap()
+    };
+
+    game.submit(Command::Move { delta_x, delta_y });
+    assert_eq!(
+        *game.ecs.fetch::<Point>(),
+        Point::new(start.x + delta_x, start.y + delta_y)
+    );
+}
+
+#[test]
+fn walking_into_a_door_opens_it() {
+    let mut game = Game::new(41);
+    let start = *game.ecs.fetch::<Point>();
+    let (delta_x, delta_y) = {
+        let map = game.ecs.fetch::<Map>();
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .find(|(dx, dy)| !map.blocked[map.xy_idx(start.x + dx, start.y + dy)])
+            .unwrap()
+    };
+    let (x, y) = (start.x + delta_x, start.y + delta_y);
+    let door = spawner::door(&mut game.ecs, x, y);
+    game.submit(Command::Wait);
+    {
+        let map = game.ecs.fetch::<Map>();
+        let idx = map.xy_idx(x, y);
+        assert!(map.blocked[idx]);
+        assert!(map.is_opaque(idx));
+    }
+
+    game.submit(Command::Move { delta_x, delta_y });
+    assert_eq!(*game.ecs.fetch::<Point>(), start);
+    assert!(game.ecs.read_storage::<Door>().get(door).unwrap().open);
+    {
+        let map = game.ecs.fetch::<Map>();
+        let idx = map.xy_idx(x, y);
+        assert!(!map.blocked[idx]);
+        assert!(!map.is_opaque(idx));
+    }
+
+    game.submit(Command::Move { delta_x, delta_y });
+    assert_eq!(*game.ecs.fetch::<Point>(), Point::new(x, y));
+}
@@ -0,0 +1,182 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+mod common;
+use common::*;
+
+fn experience(game: &Game) -> Experience {
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .read_storage::<Experience>()
+        .get(player)
+        .unwrap()
+        .clone()
+}
+
+fn attributes(game: &mut Game) -> WriteStorage<'_, Attributes> {
+    game.ecs.write_storage::<Attributes>()
+}
+
+fn attack_until_dead(game: &mut Game, monster: Entity) {
+    while game.ecs.is_alive(monster) {
+        game.submit(Command::Move {
+            delta_x: 1,
+            delta_y: 0,
+        });
+    }
+}
+
+#[test]
+fn attribute_bonuses_grow_every_two_points() {
+    let bonus = |base| Attribute { base }.bonus();
+    assert_eq!(bonus(8), -1);
+    assert_eq!(bonus(9), -1);
+    assert_eq!(bonus(10), 0);
+    assert_eq!(bonus(11), 0);
+    assert_eq!(bonus(12), 1);
+    assert_eq!(bonus(15), 2);
+}
+
+#[test]
+fn the_player_starts_at_level_one_with_average_attributes() {
+    let mut game = Game::new(61);
+    assert_eq!(experience(&game).level, 1);
+    assert_eq!(experience(&game).xp, 0);
+    let player = *game.ecs.fetch::<Entity>();
+    let attributes = attributes(&mut game);
+    let player_attributes = attributes.get(player).unwrap();
+    for attribute in [
+        player_attributes.might,
+        player_attributes.fitness,
+        player_attributes.quickness,
+        player_attributes.intelligence,
+    ] {
+        assert_eq!(attribute.bonus(), 0);
+    }
+}
+
+#[test]
+fn kills_are_worth_experience() {
+    let mut game = Game::new(61);
+    let monster = monster_next_to_player(&mut game);
+    let level = game
+        .ecs
+        .read_storage::<Experience>()
+        .get(monster)
+        .unwrap()
+        .level;
+
+    attack_until_dead(&mut game, monster);
+    assert_eq!(experience(&game).xp, xp_for_kill(level));
+}
+
+#[test]
+fn levelling_up_raises_hit_points_mana_attributes_and_skills() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<Experience>()
+        .get_mut(player)
+        .unwrap()
+        .xp = xp_to_next_level(1) - 1;
+    let monster = monster_next_to_player(&mut game);
+    attack_until_dead(&mut game, monster);
+
+    assert_eq!(experience(&game).level, 2);
+    let stats = game.ecs.read_storage::<CombatStats>();
+    let stats = stats.get(player).unwrap();
+    assert!(stats.max_hp > 30);
+    assert_eq!(stats.hp, stats.max_hp);
+    let mana = game.ecs.read_storage::<Mana>();
+    let mana = mana.get(player).unwrap();
+    assert!(mana.max_mana > 4);
+    assert_eq!(mana.mana, mana.max_mana);
+
+    let attributes = game.ecs.read_storage::<Attributes>();
+    let attributes = attributes.get(player).unwrap();
+    let total = attributes.might.base
+        + attributes.fitness.base
+        + attributes.quickness.base
+        + attributes.intelligence.base;
+    assert_eq!(total, 4 * 11 + 1);
+    let skills = game.ecs.read_storage::<Skills>();
+    let skills = skills.get(player).unwrap();
+    assert_eq!(skills.melee + skills.defense + skills.magic, 1);
+
+    let log = game.ecs.fetch::<gamelog::GameLog>();
+    assert!(log
+        .entries
+        .iter()
+        .any(|entry| entry.text == "Congratulations, you are now level 2!"));
+}
+
+#[test]
+fn might_and_the_melee_skill_hit_harder() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    attributes(&mut game).get_mut(player).unwrap().might.base = 14;
+    game.ecs
+        .write_storage::<Skills>()
+        .get_mut(player)
+        .unwrap()
+        .melee = 1;
+    let monster = monster_next_to_player(&mut game);
+
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    // Power 5 + 2 for might + 1 for skill, against monster defense 1
+    assert_eq!(hp(&game, monster), 16 - 7);
+}
+
+#[test]
+fn quickness_and_the_defense_skill_turn_blows_aside() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    attributes(&mut game)
+        .get_mut(player)
+        .unwrap()
+        .quickness
+        .base = 12;
+    game.ecs
+        .write_storage::<Skills>()
+        .get_mut(player)
+        .unwrap()
+        .defense = 1;
+    monster_next_to_player(&mut game);
+    let before = hp(&game, player);
+
+    game.submit(Command::Wait);
+    // Monster power 4 against defense 2 + 1 for quickness + 1 for skill
+    assert_eq!(hp(&game, player), before);
+}
+
+#[test]
+fn intelligence_and_the_magic_skill_strengthen_scrolls() {
+    let mut game = Game::new(61);
+    let player = *game.ecs.fetch::<Entity>();
+    attributes(&mut game)
+        .get_mut(player)
+        .unwrap()
+        .intelligence
+        .base = 14;
+    game.ecs
+        .write_storage::<Skills>()
+        .get_mut(player)
+        .unwrap()
+        .magic = 1;
+    let player_pos = *game.ecs.fetch::<Point>();
+    let scroll = spawner::magic_missile_scroll(&mut game.ecs, player_pos.x, player_pos.y);
+    game.submit(Command::PickUp);
+    let target = Point::new(player_pos.x + 2, player_pos.y);
+    let monster = spawner::random_monster(&mut game.ecs, target.x, target.y).unwrap();
+
+    game.submit(Command::UseItem {
+        item: scroll,
+        target: Some(target),
+    });
+    // 8 damage + 2 for intelligence + 1 for skill
+    assert_eq!(hp(&game, monster), 16 - 11);
+}
@@ -0,0 +1,148 @@
+use rltk::RandomNumberGenerator;
+use rust_roguelike::raws::*;
+use rust_roguelike::*;
+use specs::prelude::*;
+
+const RATS_ONLY: &str = r##"{
+    "mobs": [
+        {
+            "name": "Rat",
+            "renderable": { "glyph": "r", "fg": "#A0A0A0", "bg": "#000000", "order": 1 },
+            "vision_range": 6,
+            "stats": { "max_hp": 4, "defense": 0, "power": 2 }
+        }
+    ],
+    "items": [
+        {
+            "name": "Cheese",
+            "renderable": { "glyph": "%", "fg": "#FFFF00", "bg": "#000000", "order": 2 },
+            "consumable": { "heal": 2 }
+        }
+    ],
+    "spawn_table": [
+        { "name": "Rat", "weight": 1, "min_depth": 1, "max_depth": 100 },
+        { "name": "Cheese", "weight": 1, "min_depth": 1, "max_depth": 100 }
+    ]
+}"##;
+
+#[test]
+fn the_built_in_raws_parse() {
+    let raws = RawMaster::parse(DEFAULT_RAWS).unwrap();
+    for name in ["Goblin", "Orc", "Health Potion", "Dagger", "Shield", "Door"] {
+        assert!(raws.get(name).is_some(), "{}", name);
+    }
+}
+
+#[test]
+fn parse_errors_name_the_entry() {
+    let missing_field = r##"{ "items": [
+        { "name": "Health Potion", "consumable": { "heal": 8 } },
+        { "name": "Sword", "equippable": { "power_bonus": 3 } }
+    ] }"##;
+    let error = RawMaster::parse(missing_field).unwrap_err();
+    assert!(error.starts_with("items[1] 'Sword'"), "{}", error);
+    assert!(error.contains("slot"), "{}", error);
+
+    let typo = r##"{ "props": [ { "name": "Statue", "blocks_tiles": true } ] }"##;
+    let error = RawMaster::parse(typo).unwrap_err();
+    assert!(error.starts_with("props[0] 'Statue'"), "{}", error);
+    assert!(error.contains("blocks_tiles"), "{}", error);
+
+    let unnamed = r##"{ "mobs": [ { "vision_range": 8 } ] }"##;
+    assert!(RawMaster::parse(unnamed)
+        .unwrap_err()
+        .starts_with("mobs[0]:"));
+
+    let bad_colour = RATS_ONLY.replace("#A0A0A0", "grey");
+    let error = RawMaster::parse(&bad_colour).unwrap_err();
+    assert_eq!(error, "mobs[0] 'Rat': invalid colour 'grey'");
+
+    let unknown_spawn = RATS_ONLY.replace(
+        r#""name": "Cheese", "weight""#,
+        r#""name": "Chese", "weight""#,
+    );
+    let error = RawMaster::parse(&unknown_spawn).unwrap_err();
+    assert_eq!(error, "spawn_table[1] 'Chese': nothing is called 'Chese'");
+
+    let backwards = RATS_ONLY.replace(
+        r#""min_depth": 1, "max_depth": 100 },"#,
+        r#""min_depth": 5, "max_depth": 2 },"#,
+    );
+    let error = RawMaster::parse(&backwards).unwrap_err();
+    assert_eq!(
+        error,
+        "spawn_table[0] 'Rat': min_depth is deeper than max_depth"
+    );
+
+    let throwing_sword = r##"{ "items": [ {
+        "name": "Throwing Sword",
+        "consumable": { "range": 6, "damage": 8 },
+        "equippable": { "slot": "Melee", "power_bonus": 4 }
+    } ] }"##;
+    let error = RawMaster::parse(throwing_sword).unwrap_err();
+    assert_eq!(
+        error,
+        "items[0] 'Throwing Sword': can't be both consumable and equippable"
+    );
+
+    let random_door = DEFAULT_RAWS.replacen(
+        r#""spawn_table": ["#,
+        r#""spawn_table": [
+        { "name": "Door", "weight": 1, "min_depth": 1, "max_depth": 100 },"#,
+        1,
+    );
+    let error = RawMaster::parse(&random_door).unwrap_err();
+    assert_eq!(
+        error,
+        "spawn_table[0] 'Door': props can't be spawned at random"
+    );
+}
+
+#[test]
+fn spawn_tables_follow_depth_and_weight() {
+    let raws = RawMaster::default();
+    let mut rng = RandomNumberGenerator::seeded(23);
+    let first_level: Vec<String> = (0..200)
+        .filter_map(|_| raws.item_table(1).roll(&mut rng).map(str::to_string))
+        .collect();
+    assert_eq!(first_level.len(), 200);
+    assert!(!first_level.iter().any(|name| name == "Fireball Scroll"));
+    let potions = first_level
+        .iter()
+        .filter(|name| *name == "Health Potion")
+        .count();
+    let shields = first_level.iter().filter(|name| *name == "Shield").count();
+    assert!(potions > shields);
+
+    assert!((0..200).any(|_| raws.item_table(2).roll(&mut rng) == Some("Fireball Scroll")));
+    assert!((0..50).all(|_| matches!(
+        raws.monster_table(3).roll(&mut rng),
+        Some("Goblin") | Some("Orc")
+    )));
+    assert!(RandomTable::new().roll(&mut rng).is_none());
+}
+
+#[test]
+fn games_spawn_from_the_raws_they_are_given() {
+    let game = Game::new_with_raws(23, RawMaster::parse(RATS_ONLY).unwrap());
+    let names = game.ecs.read_storage::<Name>();
+    let monsters = game.ecs.read_storage::<Monster>();
+    let items = game.ecs.read_storage::<Item>();
+    let mut spawned = 0;
+    for (name, _monster) in (&names, &monsters).join() {
+        assert_eq!(name.name, "Rat");
+        spawned += 1;
+    }
+    for (name, _item) in (&names, &items).join() {
+        assert_eq!(name.name, "Cheese");
+        spawned += 1;
+    }
+    assert!(spawned > 0);
+
+    let stats = game.ecs.read_storage::<CombatStats>();
+    let viewsheds = game.ecs.read_storage::<Viewshed>();
+    for (_monster, stats, viewshed) in (&monsters, &stats, &viewsheds).join() {
+        assert_eq!((stats.max_hp, stats.power, stats.defense), (4, 2, 0));
+        assert_eq!(viewshed.range, 6);
+    }
+}
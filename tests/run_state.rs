@@ -0,0 +1,47 @@
+use rust_roguelike::*;
+use specs::prelude::*;
+
+fn monster_positions(game: &Game) -> Vec<(i32, i32)> {
+    let positions = game.ecs.read_storage::<Position>();
+    let monsters = game.ecs.read_storage::<Monster>();
+    (&positions, &monsters)
+        .join()
+        .map(|(pos, _monster)| (pos.x, pos.y))
+        .collect()
+}
+
+#[test]
+fn a_new_game_waits_for_input() {
+    let game = Game::new(3);
+    assert_eq!(game.run_state(), RunState::AwaitingInput);
+}
+
+#[test]
+fn the_world_does_not_advance_without_input() {
+    let mut game = Game::new(3);
+    let corner = {
+        let room = game.ecs.fetch::<Map>().rooms[0];
+        (room.x1 + 1, room.y1 + 1)
+    };
+    spawner::random_monster(&mut game.ecs, corner.0, corner.1).unwrap();
+    let before = monster_positions(&game);
+
+    for _ in 0..60 {
+        assert_eq!(game.tick(None), RunState::AwaitingInput);
+    }
+    assert_eq!(monster_positions(&game), before);
+}
+
+#[test]
+fn a_command_runs_the_player_turn_then_the_monster_turn() {
+    let mut game = Game::new(3);
+    assert_eq!(game.tick(Some(Command::Wait)), RunState::PlayerTurn);
+    assert_eq!(game.tick(None), RunState::MonsterTurn);
+    assert_eq!(game.tick(None), RunState::AwaitingInput);
+}
+
+#[test]
+fn submit_plays_a_whole_turn() {
+    let mut game = Game::new(3);
+    assert_eq!(game.submit(Command::Wait), RunState::AwaitingInput);
+}
@@ -0,0 +1,331 @@
+use rltk::Point;
+use rust_roguelike::*;
+use specs::prelude::*;
+use std::path::PathBuf;
+
+/// Everything that should survive a save, keyed by name so entity ids don't matter.
+fn snapshot(game: &Game) -> Vec<String> {
+    let entities = game.ecs.entities();
+    let names = game.ecs.read_storage::<Name>();
+    let positions = game.ecs.read_storage::<Position>();
+    let stats = game.ecs.read_storage::<CombatStats>();
+    let backpack = game.ecs.read_storage::<InBackpack>();
+    let equipped = game.ecs.read_storage::<Equipped>();
+
+    let owner = |entity: Entity| names.get(entity).unwrap().name.clone();
+    let mut snapshot: Vec<String> = (&entities, &names)
+        .join()
+        .map(|(entity, name)| {
+            format!(
+                "{} at {:?} stats {:?} carried by {:?} equipped by {:?}",
+                name.name,
+                positions.get(entity).map(|p| (p.x, p.y)),
+                stats
+                    .get(entity)
+                    .map(|s| (s.hp, s.max_hp, s.power, s.defense)),
+                backpack.get(entity).map(|b| owner(b.owner)),
+                equipped.get(entity).map(|e| (owner(e.owner), e.slot)),
+            )
+        })
+        .collect();
+    snapshot.sort();
+
+    let map = game.ecs.fetch::<Map>();
+    snapshot.push(format!("{:?}", map.tiles));
+    snapshot.push(format!("{:?}", map.revealed_tiles));
+    snapshot.push(format!("depth {}", map.depth));
+    snapshot
+}
+
+/// Every saved component of `entity`, serialized; components that point at other
+/// entities name them instead.
+fn components_of(game: &Game, entity: Entity) -> Vec<String> {
+    let mut components = Vec::new();
+    macro_rules! serialized {
+        ($($component:ty),* $(,)?) => {
+            $(
+                if let Some(component) = game.ecs.read_storage::<$component>().get(entity) {
+                    components.push(format!(
+                        "{}: {}",
+                        stringify!($component),
+                        serde_json::to_string(component).unwrap()
+                    ));
+                }
+            )*
+        };
+    }
+    serialized!(
+        Position,
+        OtherLevelPosition,
+        Renderable,
+        Player,
+        Viewshed,
+        Monster,
+        Name,
+        BlocksTile,
+        BlocksVisibility,
+        Door,
+        CombatStats,
+        HungerClock,
+        Attributes,
+        Skills,
+        Experience,
+        Mana,
+        Item,
+        Consumable,
+        ProvidesHealing,
+        ProvidesFood,
+        InflictsDamage,
+        Ranged,
+        AreaOfEffect,
+        Confusion,
+        Equippable,
+        MeleePowerBonus,
+        DefenseBonus,
+    );
+    let names = game.ecs.read_storage::<Name>();
+    let name = |owner: Entity| names.get(owner).unwrap().name.clone();
+    if let Some(pack) = game.ecs.read_storage::<InBackpack>().get(entity) {
+        components.push(format!("InBackpack: {}", name(pack.owner)));
+    }
+    if let Some(equipped) = game.ecs.read_storage::<Equipped>().get(entity) {
+        components.push(format!(
+            "Equipped: {} {:?}",
+            name(equipped.owner),
+            equipped.slot
+        ));
+    }
+    components
+}
+
+/// The first entity called `name` that has a position, or is carried, matching `at`.
+fn find(game: &Game, name: &str, at: Option<(i32, i32)>) -> Entity {
+    let entities = game.ecs.entities();
+    let names = game.ecs.read_storage::<Name>();
+    let positions = game.ecs.read_storage::<Position>();
+    (&entities, &names)
+        .join()
+        .find(|(entity, entity_name)| {
+            entity_name.name == name && positions.get(*entity).map(|p| (p.x, p.y)) == at
+        })
+        .map(|(entity, _name)| entity)
+        .unwrap()
+}
+
+fn save_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rust_roguelike_{}.json", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn player_pos(game: &Game) -> Point {
+    *game.ecs.fetch::<Point>()
+}
+
+#[test]
+fn a_round_trip_restores_the_world() {
+    let mut game = Game::new(14);
+    let (x, y) = (player_pos(&game).x, player_pos(&game).y);
+    let dagger = spawner::dagger(&mut game.ecs, x, y);
+    game.submit(Command::PickUp);
+    game.submit(Command::UseItem {
+        item: dagger,
+        target: None,
+    });
+    spawner::health_potion(&mut game.ecs, x, y);
+    game.submit(Command::PickUp);
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+
+    let before = snapshot(&game);
+    let player = *game.ecs.fetch::<Entity>();
+    let (monster_name, monster_at) = {
+        let names = game.ecs.read_storage::<Name>();
+        let positions = game.ecs.read_storage::<Position>();
+        let monsters = game.ecs.read_storage::<Monster>();
+        let (name, pos, _monster) = (&names, &positions, &monsters).join().next().unwrap();
+        (name.name.clone(), Some((pos.x, pos.y)))
+    };
+    let monster = find(&game, &monster_name, monster_at);
+    let potion = find(&game, "Health Potion", None);
+    let text = game.save().unwrap();
+    let loaded = Game::load(&text).unwrap();
+
+    assert_eq!(snapshot(&loaded), before);
+    let loaded_player = *loaded.ecs.fetch::<Entity>();
+    assert_eq!(
+        components_of(&loaded, loaded_player),
+        components_of(&game, player)
+    );
+    assert_eq!(
+        components_of(&loaded, find(&loaded, &monster_name, monster_at)),
+        components_of(&game, monster)
+    );
+    assert_eq!(
+        components_of(&loaded, find(&loaded, "Health Potion", None)),
+        components_of(&game, potion)
+    );
+    assert_eq!(loaded.seed(), 14);
+    assert_eq!(player_pos(&loaded), player_pos(&game));
+    assert_eq!(loaded.run_state(), RunState::AwaitingInput);
+}
+
+#[test]
+fn saving_does_not_disturb_the_running_game() {
+    let mut game = Game::new(14);
+    let before = snapshot(&game);
+    game.save().unwrap();
+    assert_eq!(snapshot(&game), before);
+}
+
+#[test]
+fn a_loaded_game_keeps_playing() {
+    let mut game = Game::new(14);
+    let text = game.save().unwrap();
+    let mut loaded = Game::load(&text).unwrap();
+
+    let player = *loaded.ecs.fetch::<Entity>();
+    assert!(loaded.ecs.read_storage::<Player>().get(player).is_some());
+    assert_eq!(loaded.submit(Command::Wait), RunState::AwaitingInput);
+}
+
+#[test]
+fn saves_from_another_version_are_refused() {
+    let mut game = Game::new(14);
+    let text = game.save().unwrap();
+    let text = text.replacen(
+        &format!("\"version\":{}", saveload_system::SAVE_VERSION),
+        "\"version\":0",
+        1,
+    );
+    let error = Game::load(&text).err().unwrap();
+    assert!(error.contains("version 0"), "{}", error);
+}
+
+#[test]
+fn saving_returns_to_the_main_menu_and_continue_resumes() {
+    let path = save_path("resume");
+    let mut game = Game::new(14).with_save_file(&path);
+    game.submit(Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    });
+    let before = snapshot(&game);
+
+    game.tick(Some(Command::ShowPauseMenu));
+    let runstate = game.tick(Some(Command::Choose(MenuOption::SaveAndQuit)));
+    assert!(matches!(runstate, RunState::MainMenu { .. }));
+    assert!(path.exists());
+    assert!(game.menu_options().contains(&MenuOption::Continue));
+
+    assert_eq!(
+        game.tick(Some(Command::Choose(MenuOption::Continue))),
+        RunState::PreRun
+    );
+    assert_eq!(game.tick(None), RunState::AwaitingInput);
+    assert_eq!(snapshot(&game), before);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn dying_deletes_the_save() {
+    let path = save_path("permadeath");
+    let mut game = Game::new(14).with_save_file(&path);
+    game.save_to_file(&path).unwrap();
+    assert!(path.exists());
+
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 0;
+    assert_eq!(game.submit(Command::Wait), RunState::GameOver);
+    assert!(!path.exists());
+}
+
+#[test]
+fn a_save_that_cannot_be_deleted_still_ends_the_game() {
+    // A directory in the save's place can't be removed like a file
+    let path = save_path("undeletable");
+    std::fs::create_dir_all(&path).unwrap();
+    let mut game = Game::new(14).with_save_file(&path);
+
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 0;
+    assert_eq!(game.submit(Command::Wait), RunState::GameOver);
+    let log = game.ecs.fetch::<gamelog::GameLog>();
+    assert!(log
+        .entries
+        .last()
+        .unwrap()
+        .text
+        .starts_with("Unable to delete save"));
+    let _ = std::fs::remove_dir(&path);
+}
+
+#[test]
+fn levels_left_behind_are_saved_too() {
+    let mut game = Game::new(14);
+    let (x, y) = {
+        let map = game.ecs.fetch::<Map>();
+        map.find_tile(TileType::DownStairs).unwrap()
+    };
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+    game.ecs.insert(Point::new(x, y));
+    let first_level = game.ecs.fetch::<Map>().tiles.clone();
+    game.submit(Command::Descend);
+
+    let mut loaded = Game::load(&game.save().unwrap()).unwrap();
+    assert_eq!(loaded.ecs.fetch::<MasterDungeonMap>().deepest(), Some(1));
+    let frozen = loaded.ecs.read_storage::<OtherLevelPosition>().count();
+    assert_eq!(
+        frozen,
+        game.ecs.read_storage::<OtherLevelPosition>().count()
+    );
+    assert!(frozen > 0);
+
+    let (x, y) = loaded
+        .ecs
+        .fetch::<Map>()
+        .find_tile(TileType::UpStairs)
+        .unwrap();
+    assert_eq!(*loaded.ecs.fetch::<Point>(), Point::new(x, y));
+    loaded.submit(Command::Ascend);
+    assert_eq!(loaded.ecs.fetch::<Map>().tiles, first_level);
+}
+
+#[test]
+fn a_loaded_game_generates_the_same_levels_as_the_original() {
+    let mut game = Game::new(14);
+    let (x, y) = game
+        .ecs
+        .fetch::<Map>()
+        .find_tile(TileType::DownStairs)
+        .unwrap();
+    let player = *game.ecs.fetch::<Entity>();
+    game.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+    game.ecs.insert(Point::new(x, y));
+
+    let mut loaded = Game::load(&game.save().unwrap()).unwrap();
+    game.submit(Command::Descend);
+    loaded.submit(Command::Descend);
+    assert_eq!(
+        loaded.ecs.fetch::<Map>().tiles,
+        game.ecs.fetch::<Map>().tiles
+    );
+    assert_eq!(snapshot(&loaded), snapshot(&game));
+}